@@ -0,0 +1,94 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use nvee::{Document, Key, Parse, Value};
+
+/// A document with `tables` tables of `fields` fields each, plus as many top-level fields.
+fn document(tables: usize, fields: usize) -> String {
+    let mut s = String::from("# generated\n");
+
+    for field in 0..fields {
+        s += &format!("top_{field} = \"value {field}\"\n");
+    }
+
+    for table in 0..tables {
+        s += &format!("\n[service_{table}.config]\n");
+
+        for field in 0..fields {
+            s += &format!("field_{field} = {}\n", table * fields + field);
+        }
+    }
+
+    s
+}
+
+fn parse(c: &mut Criterion) {
+    let small = document(4, 8);
+    let large = document(64, 32);
+
+    c.bench_function("parse small", |b| {
+        b.iter(|| Document::parse_str(black_box(&small)).unwrap())
+    });
+
+    c.bench_function("parse large", |b| {
+        b.iter(|| Document::parse_str(black_box(&large)).unwrap())
+    });
+
+    // As big as a flattened config gets: every table and document holds thousands of keys.
+    let flat = document(2, 4096);
+
+    c.bench_function("parse flat", |b| {
+        b.iter(|| Document::parse_str(black_box(&flat)).unwrap())
+    });
+}
+
+fn insert(c: &mut Criterion) {
+    let names: Vec<String> = (0..4096).map(|field| format!("field_{field}")).collect();
+    let keys: Vec<Key> = names
+        .iter()
+        .map(|name| Key::from_segments(["config", name.as_str()]).unwrap())
+        .collect();
+
+    c.bench_function("insert flat", |b| {
+        b.iter(|| {
+            let mut document = Document::new();
+
+            for (index, key) in keys.iter().enumerate() {
+                document.insert(key.clone(), Value::Integer(index as u64));
+            }
+
+            document
+        })
+    });
+}
+
+fn expand(c: &mut Criterion) {
+    let large = document(64, 32);
+    let parsed = Document::parse_str(&large).unwrap();
+
+    c.bench_function("vars large", |b| {
+        b.iter(|| black_box(parsed.clone()).vars().count())
+    });
+
+    c.bench_function("table fields large", |b| {
+        b.iter(|| {
+            black_box(&parsed)
+                .tables()
+                .flat_map(|table| table.fields())
+                .count()
+        })
+    });
+}
+
+fn lookup(c: &mut Criterion) {
+    let large = document(64, 32);
+    let parsed = Document::parse_str(&large).unwrap();
+    let key = Key::parse_str("service_63.config.field_31").unwrap();
+
+    c.bench_function("get large", |b| {
+        b.iter(|| black_box(&parsed).get(black_box(&key)))
+    });
+}
+
+criterion_group!(benches, parse, insert, expand, lookup);
+criterion_main!(benches);
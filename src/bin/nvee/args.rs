@@ -0,0 +1,134 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use nvee::{schema, Error, Layers, LoadOptions, Schema};
+
+/// The options every subcommand accepts, before its own arguments.
+#[derive(Debug, Default)]
+pub struct Options {
+    files: Vec<PathBuf>,
+    profile: Option<String>,
+    schema: Option<Schema>,
+}
+
+impl Options {
+    /// The file read when none is given with `-f`.
+    pub const DEFAULT_FILE: &'static str = ".nvee";
+
+    /**
+    Parses options from the start of `args`, returning them along with the arguments after them.
+
+    Options end at `--` or the first argument that isn't one, so a command's own flags are left alone.
+    */
+    pub fn parse(
+        args: impl IntoIterator<Item = OsString>,
+    ) -> Result<(Self, Vec<OsString>), String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.to_str() {
+                Some("-f" | "--file") => options.files.push(value(&mut args, "--file")?.into()),
+                Some("-p" | "--profile") => {
+                    let profile = value(&mut args, "--profile")?
+                        .into_string()
+                        .map_err(|_| "the profile isn't valid UTF-8".to_owned())?;
+
+                    options.profile = Some(profile);
+                }
+                Some("--schema") => {
+                    let path = PathBuf::from(value(&mut args, "--schema")?);
+                    let schema = Schema::read(&path).map_err(|err| match err {
+                        // Parse errors start with a line and column, which read as part of the path.
+                        schema::Error::Parse(_) => format!("{}:{err}", path.display()),
+                        err => format!("{}: {err}", path.display()),
+                    })?;
+
+                    options.schema = Some(schema);
+                }
+                Some("--") => return Ok((options, args.collect())),
+                Some(flag) if flag.starts_with('-') && flag != "-" => {
+                    return Err(format!("unknown option `{flag}`"))
+                }
+                _ => return Ok((options, std::iter::once(arg).chain(args).collect())),
+            }
+        }
+
+        Ok((options, Vec::new()))
+    }
+
+    pub fn load_options(&self) -> LoadOptions {
+        match &self.schema {
+            Some(schema) => self.unvalidated_options().schema(schema.clone()),
+            None => self.unvalidated_options(),
+        }
+    }
+
+    /// Like [`load_options`](Self::load_options), but without the schema, for commands that validate documents themselves.
+    pub fn unvalidated_options(&self) -> LoadOptions {
+        let options = LoadOptions::new().on_warning(|issue| eprintln!("warning: {issue}"));
+
+        match &self.profile {
+            Some(profile) => options.profile(profile),
+            None => options,
+        }
+    }
+
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    /// The files given with `-f`, or else [`DEFAULT_FILE`](Self::DEFAULT_FILE).
+    pub fn files(&self) -> Vec<PathBuf> {
+        if self.files.is_empty() {
+            vec![Self::DEFAULT_FILE.into()]
+        } else {
+            self.files.clone()
+        }
+    }
+
+    /// Reads every file, in order, so that later ones override earlier ones.
+    pub fn layers(&self) -> Result<Layers, Error> {
+        let mut layers = self.load_options().layers();
+
+        for file in self.files() {
+            layers.push_path(file)?;
+        }
+
+        Ok(layers)
+    }
+
+    /// Reads, merges and prepares every file, with the schema's defaults set, and expands them into variables.
+    pub fn vars(&self) -> Result<Vec<(String, String)>, Error> {
+        let load_options = self.load_options();
+        let layers = self.layers()?;
+
+        load_options.vars(load_options.prepare_layers(&layers)?)
+    }
+}
+
+/// Removes a subcommand's own option, like `--shell bash`, from `args`, before they're parsed as [`Options`].
+pub fn take_option(args: &mut Vec<OsString>, names: &[&str]) -> Result<Option<OsString>, String> {
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    let Some(index) = args[..end]
+        .iter()
+        .position(|arg| names.iter().any(|name| arg == name))
+    else {
+        return Ok(None);
+    };
+
+    if index + 1 >= end {
+        return Err(format!("`{}` needs a value", names[0]));
+    }
+
+    let value = args.remove(index + 1);
+    args.remove(index);
+
+    Ok(Some(value))
+}
+
+fn value(args: &mut impl Iterator<Item = OsString>, name: &str) -> Result<OsString, String> {
+    args.next().ok_or_else(|| format!("`{name}` needs a value"))
+}
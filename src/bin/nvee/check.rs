@@ -0,0 +1,128 @@
+use std::{error::Error, ffi::OsString, fmt::Display, path::Path, process::ExitCode};
+
+use nvee::{Layers, LoadOptions};
+
+use crate::args::Options;
+
+/**
+`nvee check [-p PROFILE] [--schema SCHEMA] [FILE]...`
+
+Merges the files like `run -f` does, later ones overriding earlier ones, along with everything they include and reference.
+Then checks the merged document the way it would be loaded: its variable names must be distinct and allowed by the naming options,
+its arrays must not contain their delimiter and, given a schema, it must follow every rule.
+Problems are printed as `path:line:column: message` where the location is known.
+Exits with 1 if there's any error; warnings, like names shadowing `PATH`, don't count.
+*/
+pub fn check(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Box<dyn Error>> {
+    let (options, files) = Options::parse(args)?;
+
+    let files = if files.is_empty() {
+        options.files()
+    } else {
+        files.into_iter().map(Into::into).collect()
+    };
+
+    let load_options = options.unvalidated_options();
+    let mut layers = load_options.layers();
+    let mut valid = true;
+
+    for path in &files {
+        if let Err(err) = layers.push_path(path) {
+            valid = report(path, &err);
+        }
+    }
+
+    if valid {
+        valid = check_layers(&options, &load_options, &layers);
+    }
+
+    Ok(if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Prints the problems with the document merged from `layers`, returning whether it's valid.
+fn check_layers(options: &Options, load_options: &LoadOptions, layers: &Layers) -> bool {
+    // Problems that can't be located are reported against the topmost file, which names the document.
+    let Some(topmost) = layers.paths().last() else {
+        return true;
+    };
+
+    let doc = match load_options.prepare_layers(layers) {
+        Ok(doc) => doc,
+        Err(err) => return report(topmost, &err),
+    };
+
+    let naming = load_options.naming_strategy();
+    let mut valid = true;
+
+    if let Err(collision) = doc.check_collisions(naming) {
+        valid = false;
+        print(
+            layers.locate_key(collision.second()),
+            topmost,
+            "error",
+            collision,
+        );
+    }
+
+    for issue in doc.check_names(naming, load_options.name_strictness()) {
+        let severity = if issue.problem().is_warning() {
+            "warning"
+        } else {
+            "error"
+        };
+
+        valid &= issue.problem().is_warning();
+        print(layers.locate_key(issue.key()), topmost, severity, &issue);
+    }
+
+    let format = load_options.value_format();
+
+    if let Some(key) = doc.find_ambiguous_array(format) {
+        valid = false;
+
+        let err = nvee::Error::Delimiter {
+            key: key.to_string(),
+            delimiter: format.delimiter_for(*key.last_segment()).to_owned(),
+        };
+        print(layers.locate_key(&key), topmost, "error", err);
+    }
+
+    if let Some(Err(violations)) = options.schema().map(|schema| doc.validate(schema)) {
+        valid = false;
+
+        for violation in violations {
+            print(layers.locate(&violation), topmost, "error", &violation);
+        }
+    }
+
+    valid
+}
+
+/// Prints a problem at `location`, or against the file at `fallback` when it has none.
+fn print(
+    location: Option<(&Path, usize, usize)>,
+    fallback: &Path,
+    severity: &str,
+    problem: impl Display,
+) {
+    match location {
+        Some((path, line, column)) => {
+            eprintln!("{}:{line}:{column}: {severity}: {problem}", path.display())
+        }
+        None => eprintln!("{}: {severity}: {problem}", fallback.display()),
+    }
+}
+
+fn report(path: &Path, err: &nvee::Error) -> bool {
+    match err {
+        // These already start with the path of the file the error is in, which may be an included one.
+        nvee::Error::File { .. } => eprintln!("{err}"),
+        err => eprintln!("{}: {err}", path.display()),
+    }
+
+    false
+}
@@ -0,0 +1,76 @@
+use std::{error::Error, ffi::OsString, process::ExitCode, str::FromStr};
+
+use nvee::export::{self, Shell};
+
+use crate::args::{self, Options};
+
+/// What `nvee export` writes.
+enum Format {
+    Shell(Shell),
+    Json,
+    Dotenv,
+    Systemd,
+    Docker,
+    Kubernetes,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "dotenv" | "env" => Ok(Self::Dotenv),
+            "systemd" => Ok(Self::Systemd),
+            "docker" => Ok(Self::Docker),
+            "kubernetes" | "k8s" => Ok(Self::Kubernetes),
+            shell => shell
+                .parse()
+                .map(Self::Shell)
+                .map_err(|_| format!("unknown format `{s}`")),
+        }
+    }
+}
+
+/**
+`nvee export [-f FILE]... [-p PROFILE] [--format FORMAT] [--name NAME]`
+
+Prints every variable in another format: by default, a script setting them, for `eval "$(nvee export)"`
+in a POSIX shell. `--shell` is another name for `--format`, as in `nvee export --shell fish | source`.
+`--name` names the Kubernetes resources, `nvee` by default.
+*/
+pub fn export(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Box<dyn Error>> {
+    let mut args: Vec<_> = args.into_iter().collect();
+
+    let format = match args::take_option(&mut args, &["--format", "--shell", "-s"])? {
+        Some(format) => format.to_str().ok_or("unknown format")?.parse()?,
+        None => Format::Shell(Shell::default()),
+    };
+
+    let name = args::take_option(&mut args, &["--name"])?;
+    let name = match &name {
+        Some(name) => name.to_str().ok_or("the name isn't valid UTF-8")?,
+        None => "nvee",
+    };
+
+    let (options, rest) = Options::parse(args)?;
+
+    if let Some(arg) = rest.first() {
+        return Err(format!("unexpected argument `{}`", arg.to_string_lossy()).into());
+    }
+
+    let vars = options.vars()?;
+
+    let output = match format {
+        Format::Shell(shell) => shell.export(vars)?,
+        Format::Json => export::json(vars),
+        Format::Dotenv => export::dotenv(vars)?,
+        Format::Systemd => export::systemd(vars)?,
+        Format::Docker => export::docker(vars)?,
+        Format::Kubernetes => export::kubernetes(name, vars)?,
+    };
+
+    print!("{output}");
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,50 @@
+use std::{error::Error, ffi::OsString, process::ExitCode};
+
+use nvee::{Key, Parse};
+
+use crate::args::Options;
+
+/**
+`nvee get [-f FILE]... [-p PROFILE] NAME`
+
+Prints the expanded value of one variable, given either as a key in the documents, like `db.port`,
+or as a variable name, like `APP_DB_PORT`. Exits with 1 if nothing matches.
+*/
+pub fn get(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Box<dyn Error>> {
+    let (options, rest) = Options::parse(args)?;
+
+    let [name] = rest.as_slice() else {
+        return Err("`get` needs a single key or variable name".into());
+    };
+    let name = name.to_str().ok_or("the name isn't valid UTF-8")?;
+
+    let layers = options.layers()?;
+    let load_options = options.load_options();
+    let doc = load_options.prepare_layers(&layers)?;
+
+    // Keys are looked up first, since a single-segment key like `port` is also a valid variable name.
+    let by_key = Key::parse_str(name).ok().and_then(|key| {
+        let format = load_options.value_format().format_for(*key.last()?);
+        doc.get(&key).map(|value| value.var_with(&format))
+    });
+
+    let value = match by_key {
+        Some(value) => Some(value),
+        None => load_options
+            .vars(doc)?
+            .into_iter()
+            .find(|(var, _)| var == name)
+            .map(|(_, value)| value),
+    };
+
+    match value {
+        Some(value) => {
+            println!("{value}");
+            Ok(ExitCode::SUCCESS)
+        }
+        None => {
+            eprintln!("nvee: `{name}` isn't set");
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
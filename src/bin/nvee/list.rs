@@ -0,0 +1,34 @@
+use std::{error::Error, ffi::OsString, process::ExitCode};
+
+use crate::args::Options;
+
+/// Printed in place of the values of variables that look like secrets.
+const REDACTED: &str = "********";
+
+/**
+`nvee list [-f FILE]... [-p PROFILE]`
+
+Prints every variable the documents expand to as `NAME=value`. The values of variables whose names
+look like secrets, such as `DB_PASSWORD`, are redacted; use `nvee get` to see one.
+*/
+pub fn list(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Box<dyn Error>> {
+    let (options, rest) = Options::parse(args)?;
+
+    if let Some(arg) = rest.first() {
+        return Err(format!("unexpected argument `{}`", arg.to_string_lossy()).into());
+    }
+
+    let vars = options.vars()?;
+
+    for (name, value) in vars {
+        let value = if nvee::secret::is_secret(&name) {
+            REDACTED
+        } else {
+            &value
+        };
+
+        println!("{name}={value}");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
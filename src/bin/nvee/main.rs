@@ -0,0 +1,54 @@
+use std::process::ExitCode;
+
+mod args;
+mod check;
+mod export;
+mod get;
+mod list;
+mod run;
+
+const USAGE: &str = "\
+usage: nvee <command> [options]
+
+commands:
+    run [--] <command> [args]...    run a command with the documents' variables
+    check [file]...                 check that documents load, have portable names and
+                                    follow the schema
+    get <key or name>               print one variable, like `db.port` or `APP_DB_PORT`
+    list                            print every variable, with secrets redacted
+    export [--format <format>]      print every variable as a script for sh (default), bash,
+                                    zsh, fish, powershell or nu, or as json, dotenv, systemd,
+                                    docker or kubernetes (with --name <name>)
+
+options:
+    -f, --file <path>       read a document, later ones overriding earlier ones (default: .nvee)
+    -p, --profile <name>    apply a profile, overriding NVEE_PROFILE
+    --schema <path>         check documents against a schema and fill in its defaults
+";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1);
+    let command = args.next();
+
+    let result = match command.as_ref().and_then(|command| command.to_str()) {
+        Some("run") => run::run(args),
+        Some("check") => check::check(args),
+        Some("get") => get::get(args),
+        Some("list") => list::list(args),
+        Some("export") => export::export(args),
+        Some("-h" | "--help" | "help") => {
+            print!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(command) => Err(format!("unknown command `{command}`\n\n{USAGE}").into()),
+        None => {
+            eprint!("{USAGE}");
+            Ok(ExitCode::from(2))
+        }
+    };
+
+    result.unwrap_or_else(|err| {
+        eprintln!("nvee: {err}");
+        ExitCode::from(2)
+    })
+}
@@ -0,0 +1,54 @@
+use std::{error::Error, ffi::OsString, io, process::Command, process::ExitCode};
+
+use crate::args::Options;
+
+/**
+`nvee run [-f FILE]... [-p PROFILE] [--] COMMAND [ARGS]...`
+
+Runs `COMMAND` with the documents' variables added to its environment. Variables that are
+already set keep their value, as they do when loading into the current process.
+*/
+pub fn run(args: impl IntoIterator<Item = OsString>) -> Result<ExitCode, Box<dyn Error>> {
+    let (options, command) = Options::parse(args)?;
+    let (program, args) = command.split_first().ok_or("`run` needs a command")?;
+
+    let vars = options
+        .vars()?
+        .into_iter()
+        .filter(|(name, _)| std::env::var_os(name).is_none());
+
+    // The variables are only given to the child, so this process's environment is never changed.
+    let mut command = Command::new(program);
+    command.args(args).envs(vars);
+
+    let err = exec(command);
+    eprintln!("nvee: can't run {}: {err}", program.to_string_lossy());
+
+    // The exit codes shells use for commands that can't be found or run.
+    Ok(ExitCode::from(match err.kind() {
+        io::ErrorKind::NotFound => 127,
+        _ => 126,
+    }))
+}
+
+/// Replaces this process with `command`, so signals go straight to it and its exit status becomes nvee's.
+#[cfg(unix)]
+fn exec(mut command: Command) -> io::Error {
+    use std::os::unix::process::CommandExt;
+
+    command.exec()
+}
+
+/**
+Runs `command` and exits with its exit code.
+
+There's no `exec` outside of Unix, but console signals like Ctrl+C reach every process attached to
+the console, so the child receives them without forwarding.
+*/
+#[cfg(not(unix))]
+fn exec(mut command: Command) -> io::Error {
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+        Err(err) => err,
+    }
+}
@@ -0,0 +1,49 @@
+use super::Error;
+
+/**
+Writes variables as a file for `docker run --env-file`.
+
+Docker takes everything after the `=` literally, without any quoting or escapes, so values are
+written as they are, and values with line breaks or NUL characters are rejected.
+*/
+pub fn docker(vars: impl IntoIterator<Item = (String, String)>) -> Result<String, Error> {
+    let mut env = String::new();
+
+    for (name, value) in vars {
+        if !super::is_portable(&name) {
+            return Err(Error::Name(name));
+        }
+
+        if value.contains(['\n', '\r', '\0']) {
+            return Err(Error::Value {
+                name,
+                reason: "contains a line break or NUL, which a docker env file can't hold",
+            });
+        }
+
+        env.push_str(&name);
+        env.push('=');
+        env.push_str(&value);
+        env.push('\n');
+    }
+
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::docker;
+    use crate::export::Error;
+
+    #[test]
+    fn literal() {
+        assert_eq!(
+            docker([("A".to_owned(), " 'quoted' \"$HOME\" # x".to_owned())]).as_deref(),
+            Ok("A= 'quoted' \"$HOME\" # x\n")
+        );
+        assert!(matches!(
+            docker([("A".to_owned(), "line\nbreak".to_owned())]),
+            Err(Error::Value { name, .. }) if name == "A"
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+use super::Error;
+
+/**
+Writes variables as a `.env` file, for tools like docker-compose that only understand that format.
+
+Values are left unquoted when they only hold letters, digits and punctuation like `/` and `:`,
+single-quoted, so they're taken literally, when they hold no `'`, and double-quoted with escapes otherwise.
+Everything written can be read back with [`import::dotenv`](crate::import::dotenv).
+
+```
+let vars = [
+    ("URL".to_owned(), "postgres://localhost".to_owned()),
+    ("GREETING".to_owned(), "hello $USER".to_owned()),
+];
+
+assert_eq!(
+    nvee::export::dotenv(vars).unwrap(),
+    "URL=postgres://localhost\nGREETING='hello $USER'\n"
+);
+```
+*/
+pub fn dotenv(vars: impl IntoIterator<Item = (String, String)>) -> Result<String, Error> {
+    let mut env = String::new();
+
+    for (name, value) in vars {
+        if !super::is_portable(&name) {
+            return Err(Error::Name(name));
+        }
+
+        env.push_str(&name);
+        env.push('=');
+
+        if !value.is_empty() && value.chars().all(super::is_plain) {
+            env.push_str(&value);
+        } else if !value.contains('\'') {
+            env.push('\'');
+            env.push_str(&value);
+            env.push('\'');
+        } else {
+            env.push('"');
+
+            for ch in value.chars() {
+                match ch {
+                    '"' | '\\' | '$' => {
+                        env.push('\\');
+                        env.push(ch);
+                    }
+                    '\n' => env.push_str("\\n"),
+                    '\r' => env.push_str("\\r"),
+                    '\t' => env.push_str("\\t"),
+                    _ => env.push(ch),
+                }
+            }
+
+            env.push('"');
+        }
+
+        env.push('\n');
+    }
+
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dotenv;
+    use crate::{export::Error, import};
+
+    #[test]
+    fn round_trip() {
+        let vars: Vec<_> = [
+            ("PLAIN", "postgres://user@localhost:5432/db"),
+            ("EMPTY", ""),
+            ("SPACES", "  hello world # not a comment "),
+            ("SINGLE", "say \"hi\"\n$HOME"),
+            ("DOUBLE", "it's \"quoted\"\\\n\t$HOME"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .collect();
+
+        let env = dotenv(vars.clone()).expect("vars should export");
+        let read: Vec<_> = import::dotenv::parse(&env)
+            .expect("exported dotenv should parse")
+            .document()
+            .expect("names should be valid")
+            .vars()
+            .collect();
+
+        assert_eq!(read, vars);
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            dotenv([("A~B".to_owned(), "1".to_owned())]),
+            Err(Error::Name("A~B".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+/**
+Writes variables as a flat JSON object, with one member per variable, in order.
+
+```
+let vars = [("GREETING".to_owned(), "say \"hi\"".to_owned())];
+
+assert_eq!(nvee::export::json(vars), "{\n  \"GREETING\": \"say \\\"hi\\\"\"\n}\n");
+```
+*/
+pub fn json(vars: impl IntoIterator<Item = (String, String)>) -> String {
+    let mut json = String::from("{");
+
+    for (index, (name, value)) in vars.into_iter().enumerate() {
+        json.push_str(if index == 0 { "\n  " } else { ",\n  " });
+        string(&mut json, &name);
+        json.push_str(": ");
+        string(&mut json, &value);
+    }
+
+    json.push_str(if json.len() == 1 { "}\n" } else { "\n}\n" });
+    json
+}
+
+/// Writes `s` as a JSON string, which is also a valid YAML double-quoted string.
+pub(super) fn string(out: &mut String, s: &str) {
+    out.push('"');
+
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", u32::from(ch));
+            }
+            ch => out.push(ch),
+        }
+    }
+
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json;
+
+    #[test]
+    fn objects() {
+        assert_eq!(json([]), "{}\n");
+        assert_eq!(
+            json([
+                ("A".to_owned(), "line\nbreak\u{7}".to_owned()),
+                ("B".to_owned(), "back\\slash".to_owned()),
+            ]),
+            "{\n  \"A\": \"line\\nbreak\\u0007\",\n  \"B\": \"back\\\\slash\"\n}\n"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use super::{json, Error};
+
+/**
+Writes variables as a Kubernetes `ConfigMap` named `name`, along with a `Secret` of the same name
+holding the variables that look like [secrets](crate::secret::is_secret), base64-encoded.
+
+The `Secret` is left out if there are no secrets. Both can be applied with `kubectl apply -f`,
+and used by a container with `envFrom`.
+
+```
+let vars = [
+    ("DB_HOST".to_owned(), "db".to_owned()),
+    ("DB_PASSWORD".to_owned(), "hunter2".to_owned()),
+];
+
+assert_eq!(
+    nvee::export::kubernetes("app", vars).unwrap(),
+    "\
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: \"app\"
+data:
+  DB_HOST: \"db\"
+---
+apiVersion: v1
+kind: Secret
+metadata:
+  name: \"app\"
+type: Opaque
+data:
+  DB_PASSWORD: \"aHVudGVyMg==\"
+"
+);
+```
+*/
+pub fn kubernetes(
+    name: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<String, Error> {
+    let mut config = Vec::new();
+    let mut secrets = Vec::new();
+
+    for (var, value) in vars {
+        // Keys of `data` can only hold these, and environment variables from `envFrom` must be portable too.
+        if !super::is_portable(&var) {
+            return Err(Error::Name(var));
+        }
+
+        if crate::secret::is_secret(&var) {
+            secrets.push((var, base64(value.as_bytes())));
+        } else {
+            config.push((var, value));
+        }
+    }
+
+    let mut manifest = String::new();
+    write_resource(&mut manifest, "ConfigMap", name, &config);
+
+    if !secrets.is_empty() {
+        manifest.push_str("---\n");
+        write_resource(&mut manifest, "Secret", name, &secrets);
+    }
+
+    Ok(manifest)
+}
+
+fn write_resource(manifest: &mut String, kind: &str, name: &str, data: &[(String, String)]) {
+    manifest.push_str("apiVersion: v1\nkind: ");
+    manifest.push_str(kind);
+    manifest.push_str("\nmetadata:\n  name: ");
+    json::string(manifest, name);
+    manifest.push('\n');
+
+    if kind == "Secret" {
+        manifest.push_str("type: Opaque\n");
+    }
+
+    if data.is_empty() {
+        manifest.push_str("data: {}\n");
+        return;
+    }
+
+    manifest.push_str("data:\n");
+
+    for (var, value) in data {
+        manifest.push_str("  ");
+        manifest.push_str(var);
+        manifest.push_str(": ");
+        json::string(manifest, value);
+        manifest.push('\n');
+    }
+}
+
+/// Encodes `bytes` as standard, padded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | u32::from(byte) << (16 - 8 * index)
+            });
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[(group >> (18 - 6 * index)) as usize & 63],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base64, kubernetes};
+    use crate::export::Error;
+
+    #[test]
+    fn encodes_base64() {
+        for (bytes, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("hunter2", "aHVudGVyMg=="),
+        ] {
+            assert_eq!(base64(bytes.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn config_only() {
+        assert_eq!(
+            kubernetes("app", [("MESSAGE".to_owned(), "a: \"b\"\n".to_owned())]).as_deref(),
+            Ok("apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: \"app\"\ndata:\n  MESSAGE: \"a: \\\"b\\\"\\n\"\n")
+        );
+        assert_eq!(
+            kubernetes("app", [("A~B".to_owned(), "1".to_owned())]),
+            Err(Error::Name("A~B".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,60 @@
+/*!
+Writing expanded variables in the formats other tools read them from.
+
+Every exporter takes variables as `(name, value)` pairs, like the ones [`Document::vars`](crate::Document::vars)
+and [`LoadOptions::vars`](crate::LoadOptions::vars) return. Exporters for formats that can't hold every name
+or value fail with an [`Error`] instead of writing something the reading tool would misunderstand.
+*/
+
+use std::fmt;
+
+mod docker;
+pub use docker::docker;
+
+mod dotenv;
+pub use dotenv::dotenv;
+
+mod json;
+pub use json::json;
+
+mod kubernetes;
+pub use kubernetes::kubernetes;
+
+mod shell;
+pub use shell::Shell;
+
+mod systemd;
+pub use systemd::systemd;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A variable name the format can't write, like `A~B` in a POSIX shell.
+    Name(String),
+    /// A value the format can't write, along with the name of its variable and why.
+    Value { name: String, reason: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name} can't be used as a variable name in this format"),
+            Self::Value { name, reason } => write!(f, "the value of {name} {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Whether `name` is a portable variable name: ASCII letters, digits and `_`, not starting with a digit.
+fn is_portable(name: &str) -> bool {
+    !name.starts_with(|ch: char| ch.is_ascii_digit())
+        && !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// Characters a value can hold without being quoted, in formats that allow unquoted values.
+fn is_plain(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.' | ',' | '/' | ':' | '@' | '+' | '%')
+}
@@ -0,0 +1,233 @@
+use std::str::FromStr;
+
+use super::Error;
+
+/**
+A shell to write variables for, so they can be loaded with `eval "$(nvee export)"` or an equivalent.
+
+```
+use nvee::{export::Shell, Document, Parse};
+
+let doc = Document::parse_str("greeting = \"it's me\"").unwrap();
+
+assert_eq!(
+    Shell::Posix.export(doc.vars()).unwrap(),
+    "export GREETING='it'\\''s me'\n"
+);
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shell {
+    /// `sh`, `bash`, `zsh` and other POSIX shells: `export NAME='value'`.
+    #[default]
+    Posix,
+    /// `set -gx NAME 'value'`.
+    Fish,
+    /// `$env:NAME = 'value'`.
+    PowerShell,
+    /// Nushell: `$env.NAME = "value"`.
+    Nu,
+}
+
+impl Shell {
+    /// Writes a line setting each variable, in order.
+    pub fn export(self, vars: impl IntoIterator<Item = (String, String)>) -> Result<String, Error> {
+        let mut script = String::new();
+
+        for (name, value) in vars {
+            self.write_var(&mut script, &name, &value)?;
+            script.push('\n');
+        }
+
+        Ok(script)
+    }
+
+    fn write_var(self, script: &mut String, name: &str, value: &str) -> Result<(), Error> {
+        match self {
+            Self::Posix | Self::Fish if !super::is_portable(name) => {
+                return Err(Error::Name(name.to_owned()))
+            }
+            Self::Posix => script.push_str("export "),
+            Self::Fish => script.push_str("set -gx "),
+            Self::PowerShell if super::is_portable(name) => script.push_str("$env:"),
+            Self::PowerShell => script.push_str("${env:"),
+            Self::Nu => script.push_str("$env."),
+        }
+
+        match self {
+            Self::Posix => {
+                script.push_str(name);
+                script.push('=');
+                quote_escaped(script, value, '\'', |ch| {
+                    (ch == '\'').then(|| "'\\''".to_owned())
+                });
+            }
+            Self::Fish => {
+                script.push_str(name);
+                script.push(' ');
+                quote_escaped(script, value, '\'', |ch| match ch {
+                    '\'' | '\\' => Some(format!("\\{ch}")),
+                    _ => None,
+                });
+            }
+            Self::PowerShell => {
+                if super::is_portable(name) {
+                    script.push_str(name);
+                } else {
+                    // Braced variable names escape `}` and the escape character itself with a backtick.
+                    for ch in name.chars() {
+                        if matches!(ch, '}' | '`') {
+                            script.push('`');
+                        }
+
+                        script.push(ch);
+                    }
+
+                    script.push('}');
+                }
+
+                script.push_str(" = ");
+                // PowerShell also ends single-quoted strings at typographic single quotes.
+                quote_escaped(script, value, '\'', |ch| {
+                    matches!(ch, '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}')
+                        .then(|| format!("{ch}{ch}"))
+                });
+            }
+            Self::Nu => {
+                if super::is_portable(name) {
+                    script.push_str(name);
+                } else {
+                    quote_escaped(script, name, '"', nu_escape);
+                }
+
+                script.push_str(" = ");
+                quote_escaped(script, value, '"', nu_escape);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `value` between `quote`s, replacing the characters `escape` returns a replacement for.
+fn quote_escaped(
+    script: &mut String,
+    value: &str,
+    quote: char,
+    escape: impl Fn(char) -> Option<String>,
+) {
+    script.push(quote);
+
+    for ch in value.chars() {
+        match escape(ch) {
+            Some(escaped) => script.push_str(&escaped),
+            None => script.push(ch),
+        }
+    }
+
+    script.push(quote);
+}
+
+/// Escapes a character in a Nushell double-quoted string, which can't hold raw control characters.
+fn nu_escape(ch: char) -> Option<String> {
+    match ch {
+        '"' | '\\' => Some(format!("\\{ch}")),
+        '\n' => Some("\\n".to_owned()),
+        '\r' => Some("\\r".to_owned()),
+        '\t' => Some("\\t".to_owned()),
+        ch if ch.is_control() => Some(format!("\\u{{{:x}}}", u32::from(ch))),
+        _ => None,
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    /// Parses the name of a shell, like `bash` or `pwsh`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sh" | "bash" | "zsh" | "dash" | "ksh" | "posix" => Ok(Self::Posix),
+            "fish" => Ok(Self::Fish),
+            "powershell" | "pwsh" => Ok(Self::PowerShell),
+            "nu" | "nushell" => Ok(Self::Nu),
+            _ => Err(format!("unknown shell `{s}`")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shell;
+    use crate::export::Error;
+
+    fn export(shell: Shell, name: &str, value: &str) -> Result<String, Error> {
+        shell.export([(name.to_owned(), value.to_owned())])
+    }
+
+    #[test]
+    fn quoting() {
+        let value = "it's a \"test\"\n\\ $HOME `x`";
+
+        for (shell, expected) in [
+            (
+                Shell::Posix,
+                "export NAME='it'\\''s a \"test\"\n\\ $HOME `x`'\n",
+            ),
+            (
+                Shell::Fish,
+                "set -gx NAME 'it\\'s a \"test\"\n\\\\ $HOME `x`'\n",
+            ),
+            (
+                Shell::PowerShell,
+                "$env:NAME = 'it''s a \"test\"\n\\ $HOME `x`'\n",
+            ),
+            (
+                Shell::Nu,
+                "$env.NAME = \"it's a \\\"test\\\"\\n\\\\ $HOME `x`\"\n",
+            ),
+        ] {
+            assert_eq!(
+                export(shell, "NAME", value).expect("value should export"),
+                expected,
+                "{shell:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn names() {
+        for shell in [Shell::Posix, Shell::Fish] {
+            assert_eq!(
+                export(shell, "A~B", "1"),
+                Err(Error::Name("A~B".to_owned()))
+            );
+        }
+
+        assert_eq!(
+            export(Shell::PowerShell, "A}B", "1").as_deref(),
+            Ok("${env:A`}B} = '1'\n")
+        );
+        assert_eq!(
+            export(Shell::PowerShell, "NAME", "\u{2019}").as_deref(),
+            Ok("$env:NAME = '\u{2019}\u{2019}'\n")
+        );
+        assert_eq!(
+            export(Shell::Nu, "A~B", "\u{1b}").as_deref(),
+            Ok("$env.\"A~B\" = \"\\u{1b}\"\n")
+        );
+    }
+
+    #[test]
+    fn names_of_shells() {
+        for (name, shell) in [
+            ("bash", Shell::Posix),
+            ("fish", Shell::Fish),
+            ("pwsh", Shell::PowerShell),
+            ("nushell", Shell::Nu),
+        ] {
+            assert_eq!(name.parse(), Ok(shell));
+        }
+
+        assert!("cmd".parse::<Shell>().is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use super::Error;
+
+/**
+Writes variables as a systemd `EnvironmentFile`.
+
+Values that aren't plain are double-quoted, escaping `"`, `\`, `$` and `` ` ``. Values with line breaks
+or NUL characters are rejected, since systemd would read them as several lines or cut them short.
+*/
+pub fn systemd(vars: impl IntoIterator<Item = (String, String)>) -> Result<String, Error> {
+    let mut env = String::new();
+
+    for (name, value) in vars {
+        if !super::is_portable(&name) {
+            return Err(Error::Name(name));
+        }
+
+        if value.contains(['\n', '\r', '\0']) {
+            return Err(Error::Value {
+                name,
+                reason: "contains a line break or NUL, which an EnvironmentFile can't hold",
+            });
+        }
+
+        env.push_str(&name);
+        env.push('=');
+
+        if !value.is_empty() && value.chars().all(super::is_plain) {
+            env.push_str(&value);
+        } else {
+            env.push('"');
+
+            for ch in value.chars() {
+                if matches!(ch, '"' | '\\' | '$' | '`') {
+                    env.push('\\');
+                }
+
+                env.push(ch);
+            }
+
+            env.push('"');
+        }
+
+        env.push('\n');
+    }
+
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::systemd;
+    use crate::export::Error;
+
+    fn vars(iter: impl IntoIterator<Item = (&'static str, &'static str)>) -> Vec<(String, String)> {
+        iter.into_iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(
+            systemd(vars([
+                ("PLAIN", "/srv/data"),
+                ("EMPTY", ""),
+                ("QUOTED", " say \"hi\" to $USER\\`x` "),
+            ]))
+            .expect("vars should export"),
+            "PLAIN=/srv/data\nEMPTY=\"\"\nQUOTED=\" say \\\"hi\\\" to \\$USER\\\\\\`x\\` \"\n"
+        );
+    }
+
+    #[test]
+    fn unrepresentable() {
+        assert!(matches!(
+            systemd(vars([("A", "line\nbreak")])),
+            Err(Error::Value { name, .. }) if name == "A"
+        ));
+        assert_eq!(
+            systemd(vars([("A-B", "1")])),
+            Err(Error::Name("A-B".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+
+use crate::{
+    model::value::{DurationUnit, Format, SizeUnit},
+    Ident,
+};
+
+/**
+How values with several possible forms are written as variables.
+
+Arrays are joined with `,`, except under path-like keys, whose last segment ends in `path`
+(like `PATH` or `LD_LIBRARY_PATH`), which are joined with `:`.
+Durations are written in milliseconds and sizes in bytes.
+
+An item containing the delimiter it's joined with can't be told apart from two items,
+so [`LoadOptions`](crate::LoadOptions) rejects such arrays with [`Error::Delimiter`](crate::Error::Delimiter).
+
+```
+# use nvee::{model::value::DurationUnit, Document, NamingStrategy, Parse, ValueFormat};
+let document = Document::parse_str("hosts = [\"a\", \"b\"]\ntimeout = 1m30s\n").unwrap();
+let format = ValueFormat::new().delimiter(" ").duration_unit(DurationUnit::Seconds);
+
+let vars: Vec<_> = document.vars_with_format(&NamingStrategy::DEFAULT, &format).collect();
+assert_eq!(vars[0].1, "a b");
+assert_eq!(vars[1].1, "90");
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueFormat {
+    delimiter: Cow<'static, str>,
+    path_delimiter: Cow<'static, str>,
+    duration_unit: DurationUnit,
+    size_unit: SizeUnit,
+}
+
+impl Default for ValueFormat {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ValueFormat {
+    pub const DEFAULT: Self = Self {
+        delimiter: Cow::Borrowed(","),
+        path_delimiter: Cow::Borrowed(":"),
+        duration_unit: DurationUnit::Milliseconds,
+        size_unit: SizeUnit::Bytes,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the string array items are joined with, `,` by default.
+    pub fn delimiter(mut self, delimiter: impl Into<Cow<'static, str>>) -> Self {
+        self.delimiter = delimiter.into();
+        self
+    }
+
+    /// Sets the string array items under path-like keys are joined with, `:` by default.
+    pub fn path_delimiter(mut self, delimiter: impl Into<Cow<'static, str>>) -> Self {
+        self.path_delimiter = delimiter.into();
+        self
+    }
+
+    pub fn duration_unit(mut self, unit: DurationUnit) -> Self {
+        self.duration_unit = unit;
+        self
+    }
+
+    pub fn size_unit(mut self, unit: SizeUnit) -> Self {
+        self.size_unit = unit;
+        self
+    }
+
+    /// How to write the value of a field, given the last segment of its key.
+    pub fn format_for(&self, segment: Ident) -> Format<'_> {
+        Format {
+            delimiter: self.delimiter_for(segment),
+            duration: self.duration_unit,
+            size: self.size_unit,
+        }
+    }
+
+    /// The string to join an array with, given the last segment of its key.
+    pub fn delimiter_for(&self, segment: Ident) -> &str {
+        let bytes = segment.as_bytes();
+        let path_like = bytes.len() >= 4 && bytes[bytes.len() - 4..].eq_ignore_ascii_case(b"path");
+
+        if path_like {
+            &self.path_delimiter
+        } else {
+            &self.delimiter
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueFormat;
+    use crate::model::ident::CONSTRUCTOR as ident;
+
+    #[test]
+    fn delimiters() {
+        let format = ValueFormat::new().delimiter(" ");
+
+        for (segment, delimiter) in [
+            ("hosts", " "),
+            ("path", ":"),
+            ("ld_library_path", ":"),
+            ("PYTHONPATH", ":"),
+            ("paths", " "),
+        ] {
+            assert_eq!(format.delimiter_for(ident(segment)), delimiter);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+/*!
+Reading `.env` files.
+
+```
+use nvee::import::dotenv;
+
+let imported = dotenv::parse("# database\nexport DB_URL=\"postgres://localhost\"\nDB_PORT=5432 # default\n").unwrap();
+let vars: Vec<_> = imported.document().unwrap().vars().collect();
+
+assert_eq!(vars, [
+    ("DB_URL".to_owned(), "postgres://localhost".to_owned()),
+    ("DB_PORT".to_owned(), "5432".to_owned()),
+]);
+```
+
+Each non-blank line that isn't a `#` comment sets a variable, as `NAME=value` with an optional `export ` in front.
+Values may be:
+- unquoted, running until the end of the line or a `#` after whitespace, with surrounding whitespace removed,
+- single-quoted, which is taken literally,
+- or double-quoted, which understands `\n`, `\r`, `\t`, `\"`, `\\` and `\$` escapes.
+
+Quoted values may span several lines. A variable set more than once keeps its last value.
+References to other variables, like `$HOME`, aren't expanded.
+
+Each name becomes a single-segment key, so it's [named](crate::NamingStrategy) like any other key when expanded:
+`http_proxy` becomes `HTTP_PROXY` by default, and the basename of the document it ends up in is prepended.
+Expanding with [`Case::Preserve`](crate::Case::Preserve) and no basename gives back the names as written.
+Names that aren't valid [identifiers](crate::Ident), like `my.var`, fail with [`Error::Key`](super::Error::Key).
+*/
+
+use std::fmt;
+
+use super::{Imported, Leaf, Scalar};
+use crate::Located;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line with neither a comment nor a `=`.
+    MissingEquals,
+    /// A line starting with `=`.
+    EmptyName,
+    /// A quoted value that isn't closed before the end of the file.
+    UnclosedQuote(char),
+    /// Something other than a comment after a quoted value.
+    TrailingCharacters,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEquals => write!(f, "expected `NAME=value`"),
+            Self::EmptyName => write!(f, "expected a name before `=`"),
+            Self::UnclosedQuote(quote) => write!(f, "unclosed {quote} quote"),
+            Self::TrailingCharacters => write!(f, "expected a new line after the closing quote"),
+        }
+    }
+}
+
+/// Reads the variables set by the `.env` file `text`.
+pub fn parse(text: &str) -> Result<Imported, Located<ParseError>> {
+    let mut imported = Imported::default();
+    let mut reader = Reader { text, offset: 0 };
+
+    while let Some((name, value)) = reader.next_var().map_err(|error| reader.locate(error))? {
+        imported.insert(vec![name.to_owned()], Leaf::Scalar(Scalar::String(value)));
+    }
+
+    Ok(imported)
+}
+
+struct Reader<'t> {
+    text: &'t str,
+    /// Byte offset of the next unread character.
+    offset: usize,
+}
+
+impl<'t> Reader<'t> {
+    fn rest(&self) -> &'t str {
+        &self.text[self.offset..]
+    }
+
+    fn locate<E>(&self, error: E) -> Located<E> {
+        let read = &self.text[..self.offset];
+        let line_start = read.rfind('\n').map_or(0, |newline| newline + 1);
+
+        Located {
+            error,
+            line: read.matches('\n').count() + 1,
+            column: read[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Reads up to and including the next newline, returning the line without it.
+    fn line(&mut self) -> &'t str {
+        let rest = self.rest();
+        let len = rest.find('\n').unwrap_or(rest.len());
+
+        self.offset += (len + 1).min(rest.len());
+        rest[..len].strip_suffix('\r').unwrap_or(&rest[..len])
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = self.rest();
+        self.offset += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+
+    /// Reads the next variable, skipping blank lines and comments.
+    fn next_var(&mut self) -> Result<Option<(&'t str, String)>, ParseError> {
+        loop {
+            self.skip_spaces();
+
+            match self.rest().chars().next() {
+                None => return Ok(None),
+                Some('\n' | '\r' | '#') => {
+                    self.line();
+                }
+                Some(_) => break,
+            }
+        }
+
+        if let Some(rest) = self.rest().strip_prefix("export") {
+            if rest.starts_with([' ', '\t']) {
+                self.offset += "export".len();
+                self.skip_spaces();
+            }
+        }
+
+        let rest = self.rest();
+        let equals = match rest.find(['=', '\n']) {
+            Some(equals) if rest[equals..].starts_with('=') => equals,
+            _ => return Err(ParseError::MissingEquals),
+        };
+
+        let name = rest[..equals].trim_end_matches([' ', '\t']);
+
+        if name.is_empty() {
+            return Err(ParseError::EmptyName);
+        }
+
+        self.offset += equals + 1;
+        let spaced = self.rest().starts_with([' ', '\t']);
+        self.skip_spaces();
+
+        let value = match self.rest().chars().next() {
+            Some(quote @ ('\'' | '"')) => {
+                self.offset += 1;
+                let value = self.quoted(quote)?;
+
+                self.skip_spaces();
+
+                if !matches!(self.rest().chars().next(), None | Some('\n' | '\r' | '#')) {
+                    return Err(ParseError::TrailingCharacters);
+                }
+
+                self.line();
+                value
+            }
+            _ => {
+                let line = self.line();
+                // The whitespace after `=` has been skipped, so a comment may start the rest of the line.
+                let end = match spaced && line.starts_with('#') {
+                    true => Some(0),
+                    false => line.find(" #").or_else(|| line.find("\t#")),
+                };
+
+                line[..end.unwrap_or(line.len())]
+                    .trim_end_matches([' ', '\t'])
+                    .to_owned()
+            }
+        };
+
+        Ok(Some((name, value)))
+    }
+
+    /// Reads a value up to its closing `quote`, which has already been opened.
+    fn quoted(&mut self, quote: char) -> Result<String, ParseError> {
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                _ if ch == quote => {
+                    self.offset += index + 1;
+                    return Ok(value);
+                }
+                '\\' if quote == '"' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, ch @ ('"' | '\\' | '$'))) => ch,
+                        Some((_, ch)) => {
+                            value.push('\\');
+                            ch
+                        }
+                        None => break,
+                    };
+
+                    value.push(escaped);
+                }
+                _ => value.push(ch),
+            }
+        }
+
+        Err(ParseError::UnclosedQuote(quote))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, ParseError};
+
+    fn vars(text: &str) -> Vec<(String, String)> {
+        parse(text)
+            .expect("dotenv should parse")
+            .document()
+            .expect("names should be valid")
+            .vars()
+            .collect()
+    }
+
+    #[test]
+    fn values() {
+        let text = "\
+# comment
+PLAIN=hello world  # trailing comment
+export EXPORTED = 1
+  INDENTED=x
+EMPTY=
+COMMENTED= # only a comment
+SINGLE='literal \\n # not a comment'
+DOUBLE=\"line\\nbreak \\\"quoted\\\" \\$HOME \\w\"
+HASH=a#b
+MULTI=\"first
+second\"
+CRLF=windows\r
+PLAIN=replaced
+";
+
+        let expected = [
+            ("PLAIN", "replaced"),
+            ("EXPORTED", "1"),
+            ("INDENTED", "x"),
+            ("EMPTY", ""),
+            ("COMMENTED", ""),
+            ("SINGLE", "literal \\n # not a comment"),
+            ("DOUBLE", "line\nbreak \"quoted\" $HOME \\w"),
+            ("HASH", "a#b"),
+            ("MULTI", "first\nsecond"),
+            ("CRLF", "windows"),
+        ];
+
+        assert_eq!(
+            vars(text),
+            expected.map(|(name, value)| (name.to_owned(), value.to_owned()))
+        );
+    }
+
+    #[test]
+    fn names() {
+        use crate::{Case, NamingStrategy};
+
+        let imported =
+            parse("http_proxy=http://proxy\nNO_PROXY=localhost\n").expect("dotenv should parse");
+        let document = imported.document().expect("names should be valid");
+
+        assert_eq!(
+            document
+                .clone()
+                .vars()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            ["HTTP_PROXY", "NO_PROXY"]
+        );
+        assert_eq!(
+            document
+                .vars_with(&NamingStrategy::new().case(Case::Preserve))
+                .collect::<Vec<_>>(),
+            [
+                ("http_proxy".to_owned(), "http://proxy".to_owned()),
+                ("NO_PROXY".to_owned(), "localhost".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for (text, line, error) in [
+            ("A=1\nB\n", 2, ParseError::MissingEquals),
+            ("=1", 1, ParseError::EmptyName),
+            ("A=1\nB=\"open\n\n", 2, ParseError::UnclosedQuote('"')),
+            ("A='x' y", 1, ParseError::TrailingCharacters),
+        ] {
+            let err = parse(text).expect_err("dotenv should be invalid");
+
+            assert_eq!((err.error, err.line), (error, line), "{text:?}");
+        }
+    }
+}
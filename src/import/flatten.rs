@@ -0,0 +1,86 @@
+use super::{Error, Imported, Leaf, Scalar};
+
+/// What an importer does with arrays, which have no direct equivalent in environment variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArrayPolicy {
+    /**
+    Arrays of strings and numbers become a single variable with their items joined,
+    like nvee's own [arrays](crate::Value::Array). Arrays holding tables or other arrays are indexed instead.
+    */
+    #[default]
+    Join,
+    /// Every item becomes its own variable, with its index as the last key segment: `hosts.0`, `hosts.1` and so on.
+    Index,
+    /// Arrays are an error.
+    Reject,
+}
+
+/// A tree read by an importer, before it's flattened into fields.
+pub(super) enum Node {
+    Scalar(Scalar),
+    Array(Vec<Node>),
+    Table(Vec<(String, Node)>),
+}
+
+impl Node {
+    pub(super) fn string(s: impl Into<String>) -> Self {
+        Self::Scalar(Scalar::String(s.into()))
+    }
+
+    /// An integer, which is kept as text if it's negative, since nvee integers can't be.
+    pub(super) fn integer(int: i64) -> Self {
+        match u64::try_from(int) {
+            Ok(int) => Self::Scalar(Scalar::Integer(int)),
+            Err(_) => Self::string(int.to_string()),
+        }
+    }
+}
+
+impl Imported {
+    /// Flattens the entries of a table into fields, with nested tables' keys prepended to theirs.
+    pub(super) fn flatten(
+        entries: Vec<(String, Node)>,
+        policy: ArrayPolicy,
+    ) -> Result<Self, Error> {
+        let mut imported = Self::default();
+        imported.add(&mut Vec::new(), Node::Table(entries), policy)?;
+
+        Ok(imported)
+    }
+
+    fn add(&mut self, key: &mut Vec<String>, node: Node, policy: ArrayPolicy) -> Result<(), Error> {
+        match node {
+            Node::Scalar(scalar) => self.insert(key.clone(), Leaf::Scalar(scalar)),
+            Node::Table(entries) => {
+                for (name, node) in entries {
+                    key.push(name);
+                    self.add(key, node, policy)?;
+                    key.pop();
+                }
+            }
+            Node::Array(_) if policy == ArrayPolicy::Reject => {
+                return Err(Error::Array(key.join(".")))
+            }
+            Node::Array(items)
+                if policy == ArrayPolicy::Join
+                    && items.iter().all(|item| matches!(item, Node::Scalar(_))) =>
+            {
+                let scalars = items.into_iter().filter_map(|item| match item {
+                    Node::Scalar(scalar) => Some(scalar),
+                    _ => None,
+                });
+
+                self.insert(key.clone(), Leaf::Array(scalars.collect()));
+            }
+            Node::Array(items) => {
+                for (index, item) in items.into_iter().enumerate() {
+                    key.push(index.to_string());
+                    self.add(key, item, policy)?;
+                    key.pop();
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+/*!
+Flattening JSON files into fields, with the `json` feature.
+
+The file must hold an object, and nested objects become key segments, so `port` in `{"db": {"port": 5432}}`
+becomes `db.port`. Strings and non-negative integers keep their type; other numbers and booleans become
+strings in their JSON form, and `null` becomes an empty string. Arrays are handled according to an [`ArrayPolicy`].
+
+```
+use nvee::import::{json, ArrayPolicy};
+
+let text = r#"{"db": {"port": 5432}, "servers": [{"host": "a"}, {"host": "b"}]}"#;
+let imported = json::parse(text, ArrayPolicy::Join).unwrap();
+let vars: Vec<_> = imported.document().unwrap().vars().collect();
+
+assert_eq!(vars, [
+    ("DB_PORT".to_owned(), "5432".to_owned()),
+    ("SERVERS_0_HOST".to_owned(), "a".to_owned()),
+    ("SERVERS_1_HOST".to_owned(), "b".to_owned()),
+]);
+```
+*/
+
+use serde_json::{Map, Value};
+
+use super::{flatten::Node, ArrayPolicy, Error, Imported};
+
+/// Reads and flattens the JSON object `text`.
+pub fn parse(text: &str, policy: ArrayPolicy) -> Result<Imported, Error> {
+    match serde_json::from_str(text).map_err(Error::Json)? {
+        Value::Object(object) => Imported::flatten(entries(object), policy),
+        _ => Err(Error::NotATable),
+    }
+}
+
+fn entries(object: Map<String, Value>) -> Vec<(String, Node)> {
+    object
+        .into_iter()
+        .map(|(key, value)| (key, node(value)))
+        .collect()
+}
+
+fn node(value: Value) -> Node {
+    match value {
+        Value::Null => Node::string(""),
+        Value::Bool(boolean) => Node::string(boolean.to_string()),
+        Value::Number(number) => match number.as_i64() {
+            Some(int) => Node::integer(int),
+            None => Node::string(number.to_string()),
+        },
+        Value::String(s) => Node::string(s),
+        Value::Array(items) => Node::Array(items.into_iter().map(node).collect()),
+        Value::Object(object) => Node::Table(entries(object)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::import::{ArrayPolicy, Error};
+
+    fn vars(text: &str, policy: ArrayPolicy) -> Vec<(String, String)> {
+        parse(text, policy)
+            .expect("json should flatten")
+            .document()
+            .expect("keys should be valid")
+            .vars()
+            .collect()
+    }
+
+    #[test]
+    fn flattens() {
+        let text = r#"{
+            "name": "app",
+            "offset": -5,
+            "ratio": 0.5,
+            "debug": false,
+            "missing": null,
+            "hosts": ["a", 1],
+            "db": {"primary": {"port": 5432}},
+            "matrix": [[1, 2], [3]]
+        }"#;
+
+        let expected = [
+            ("NAME", "app"),
+            ("OFFSET", "-5"),
+            ("RATIO", "0.5"),
+            ("DEBUG", "false"),
+            ("MISSING", ""),
+            ("HOSTS", "a,1"),
+            ("DB_PRIMARY_PORT", "5432"),
+            ("MATRIX_0", "1,2"),
+            ("MATRIX_1", "3"),
+        ];
+
+        assert_eq!(
+            vars(text, ArrayPolicy::Join),
+            expected.map(|(name, value)| (name.to_owned(), value.to_owned()))
+        );
+    }
+
+    #[test]
+    fn array_policies() {
+        let text = r#"{"hosts": ["a", "b"]}"#;
+
+        assert_eq!(
+            vars(text, ArrayPolicy::Index),
+            [
+                ("HOSTS_0".to_owned(), "a".to_owned()),
+                ("HOSTS_1".to_owned(), "b".to_owned())
+            ]
+        );
+        assert!(matches!(
+            parse(text, ArrayPolicy::Reject),
+            Err(Error::Array(key)) if key == "hosts"
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse("{\"a\": ", ArrayPolicy::Join),
+            Err(Error::Json(_))
+        ));
+        assert!(matches!(
+            parse("[1, 2]", ArrayPolicy::Join),
+            Err(Error::NotATable)
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+/*!
+Reading variables from formats other than nvee, like `.env` files, or JSON, TOML and YAML files
+with the `json`, `toml` and `yaml` features.
+
+Importers produce an [`Imported`], which owns the text it read, and [`Imported::document`] borrows it as a [`Document`],
+so imported variables can be merged, layered and expanded like any other document.
+*/
+
+use std::{collections::HashMap, fmt};
+
+use crate::{model::key, Document, Key, Value};
+
+pub mod dotenv;
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod flatten;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub use flatten::ArrayPolicy;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "toml")]
+pub mod toml;
+
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+/// Fields read by an importer, as key segments and values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Imported {
+    fields: Vec<(Vec<String>, Leaf)>,
+    /// The position of every field in `fields`, by key.
+    index: HashMap<Vec<String>, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Scalar {
+    String(String),
+    #[cfg_attr(
+        not(any(feature = "json", feature = "toml", feature = "yaml")),
+        allow(dead_code)
+    )]
+    Integer(u64),
+}
+
+/// An imported value, which owns its text so a document can borrow it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Leaf {
+    Scalar(Scalar),
+    /// An array joined into a single variable.
+    #[cfg_attr(
+        not(any(feature = "json", feature = "toml", feature = "yaml")),
+        allow(dead_code)
+    )]
+    Array(Vec<Scalar>),
+}
+
+impl Scalar {
+    fn value(&self) -> Value<'_> {
+        match self {
+            Self::String(s) => Value::String(s),
+            Self::Integer(int) => Value::Integer(*int),
+        }
+    }
+}
+
+impl Leaf {
+    fn value(&self) -> Value<'_> {
+        match self {
+            Self::Scalar(scalar) => scalar.value(),
+            Self::Array(items) => Value::Array(items.iter().map(Scalar::value).collect()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// A key segment that can't be part of an nvee key, like an empty one.
+    Key(String, key::ParseError),
+    /// An array under the key, which `ArrayPolicy::Reject` doesn't allow.
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    Array(String),
+    /// A file that doesn't hold a table, or mapping, at the top level.
+    #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+    NotATable,
+    /// A value that can't be imported, like a YAML mapping used as a key, under the key.
+    #[cfg(feature = "yaml")]
+    Unsupported(String),
+    #[cfg(feature = "json")]
+    Json(serde_json::Error),
+    #[cfg(feature = "toml")]
+    Toml(::toml::de::Error),
+    #[cfg(feature = "yaml")]
+    Yaml(yaml_rust2::ScanError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key, err) => write!(f, "invalid key `{key}`: {err}"),
+            #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+            Self::Array(key) => write!(f, "`{key}` is an array, which isn't allowed"),
+            #[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+            Self::NotATable => write!(f, "expected a table at the top level"),
+            #[cfg(feature = "yaml")]
+            Self::Unsupported(key) => write!(f, "`{key}` holds a value that can't be imported"),
+            #[cfg(feature = "json")]
+            Self::Json(err) => err.fmt(f),
+            #[cfg(feature = "toml")]
+            Self::Toml(err) => err.fmt(f),
+            #[cfg(feature = "yaml")]
+            Self::Yaml(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Imported {
+    /// Adds a field, replacing any earlier one with the same key.
+    fn insert(&mut self, key: Vec<String>, value: Leaf) {
+        match self.index.get(&key) {
+            Some(&index) => self.fields[index].1 = value,
+            None => {
+                self.index.insert(key.clone(), self.fields.len());
+                self.fields.push((key, value));
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Builds a document holding every field, in the order they were read.
+    pub fn document(&self) -> Result<Document<'_>, Error> {
+        let mut document = Document::new();
+        for (segments, value) in &self.fields {
+            let key = Key::from_segments(segments.iter().map(String::as_str))
+                .map_err(|err| Error::Key(segments.join("."), err))?;
+
+            document.insert(key, value.value());
+        }
+
+        Ok(document)
+    }
+}
@@ -0,0 +1,133 @@
+/*!
+Flattening TOML files into fields, with the `toml` feature.
+
+Tables, including inline tables and arrays of tables, become key segments, so `port` in `[db]` becomes
+`db.port`. Strings and non-negative integers keep their type; floats, booleans, negative integers and
+date-times become strings in their TOML form. Arrays are handled according to an [`ArrayPolicy`].
+
+```
+use nvee::import::{toml, ArrayPolicy};
+
+let text = "[db]\nport = 5432\n\n[[servers]]\nhost = \"a\"\n\n[[servers]]\nhost = \"b\"\n";
+let imported = toml::parse(text, ArrayPolicy::Join).unwrap();
+let vars: Vec<_> = imported.document().unwrap().vars().collect();
+
+assert_eq!(vars, [
+    ("DB_PORT".to_owned(), "5432".to_owned()),
+    ("SERVERS_0_HOST".to_owned(), "a".to_owned()),
+    ("SERVERS_1_HOST".to_owned(), "b".to_owned()),
+]);
+```
+*/
+
+use super::{flatten::Node, ArrayPolicy, Error, Imported};
+
+/// Reads and flattens the TOML document `text`.
+pub fn parse(text: &str, policy: ArrayPolicy) -> Result<Imported, Error> {
+    let table = text.parse::<::toml::Table>().map_err(Error::Toml)?;
+
+    Imported::flatten(entries(table), policy)
+}
+
+fn entries(table: ::toml::Table) -> Vec<(String, Node)> {
+    table
+        .into_iter()
+        .map(|(key, value)| (key, node(value)))
+        .collect()
+}
+
+fn node(value: ::toml::Value) -> Node {
+    use ::toml::Value;
+
+    match value {
+        Value::String(s) => Node::string(s),
+        Value::Integer(int) => Node::integer(int),
+        Value::Float(float) => Node::string(float.to_string()),
+        Value::Boolean(boolean) => Node::string(boolean.to_string()),
+        Value::Datetime(datetime) => Node::string(datetime.to_string()),
+        Value::Array(items) => Node::Array(items.into_iter().map(node).collect()),
+        Value::Table(table) => Node::Table(entries(table)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::import::{ArrayPolicy, Error};
+
+    const TEXT: &str = r#"
+title = "app"
+offset = -5
+ratio = 0.5
+enabled = true
+released = 1979-05-27T07:32:00Z
+hosts = ["a", "b"]
+owner = { name = "kate", "e mail" = "x" }
+
+[db.primary]
+ports = [5432, 5433]
+
+[[servers]]
+host = "a"
+"#;
+
+    fn vars(text: &str, policy: ArrayPolicy) -> Vec<(String, String)> {
+        parse(text, policy)
+            .expect("toml should flatten")
+            .document()
+            .expect("keys should be valid")
+            .vars()
+            .collect()
+    }
+
+    #[test]
+    fn flattens() {
+        let text = TEXT.replace("\"e mail\"", "email");
+        let expected = [
+            ("TITLE", "app"),
+            ("OFFSET", "-5"),
+            ("RATIO", "0.5"),
+            ("ENABLED", "true"),
+            ("RELEASED", "1979-05-27T07:32:00Z"),
+            ("HOSTS", "a,b"),
+            ("OWNER_NAME", "kate"),
+            ("OWNER_EMAIL", "x"),
+            ("DB_PRIMARY_PORTS", "5432,5433"),
+            ("SERVERS_0_HOST", "a"),
+        ];
+
+        assert_eq!(
+            vars(&text, ArrayPolicy::Join),
+            expected.map(|(name, value)| (name.to_owned(), value.to_owned()))
+        );
+    }
+
+    #[test]
+    fn array_policies() {
+        let text = "hosts = [\"a\", \"b\"]";
+
+        assert_eq!(
+            vars(text, ArrayPolicy::Index),
+            [
+                ("HOSTS_0".to_owned(), "a".to_owned()),
+                ("HOSTS_1".to_owned(), "b".to_owned())
+            ]
+        );
+        assert!(matches!(
+            parse(text, ArrayPolicy::Reject),
+            Err(Error::Array(key)) if key == "hosts"
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse("a = ", ArrayPolicy::Join),
+            Err(Error::Toml(_))
+        ));
+        assert!(matches!(
+            parse(TEXT, ArrayPolicy::Join).map(|imported| imported.document().map(|_| ())),
+            Ok(Err(Error::Key(key, _))) if key == "owner.e mail"
+        ));
+    }
+}
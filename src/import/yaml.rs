@@ -0,0 +1,176 @@
+/*!
+Flattening YAML files into fields, with the `yaml` feature.
+
+The file's first document must be a mapping, and nested mappings become key segments, so `port` under `db:`
+becomes `db.port`. Strings and non-negative integers keep their type; floats, booleans and negative integers
+become strings as written, and `null` becomes an empty string. Arrays are handled according to an [`ArrayPolicy`].
+An empty file has no fields.
+
+```
+use nvee::import::{yaml, ArrayPolicy};
+
+let text = "db:\n  port: 5432\nservers:\n  - host: a\n  - host: b\n";
+let imported = yaml::parse(text, ArrayPolicy::Join).unwrap();
+let vars: Vec<_> = imported.document().unwrap().vars().collect();
+
+assert_eq!(vars, [
+    ("DB_PORT".to_owned(), "5432".to_owned()),
+    ("SERVERS_0_HOST".to_owned(), "a".to_owned()),
+    ("SERVERS_1_HOST".to_owned(), "b".to_owned()),
+]);
+```
+*/
+
+use yaml_rust2::{yaml::Hash, Yaml, YamlLoader};
+
+use super::{flatten::Node, ArrayPolicy, Error, Imported};
+
+/// Reads and flattens the first document in the YAML file `text`.
+pub fn parse(text: &str, policy: ArrayPolicy) -> Result<Imported, Error> {
+    let mut documents = YamlLoader::load_from_str(text).map_err(Error::Yaml)?;
+
+    if documents.is_empty() {
+        return Ok(Imported::default());
+    }
+
+    match documents.swap_remove(0) {
+        Yaml::Hash(hash) => Imported::flatten(entries(hash, &mut Vec::new())?, policy),
+        _ => Err(Error::NotATable),
+    }
+}
+
+/// Converts the entries of a mapping under the key `path`, which is only used for errors.
+fn entries(hash: Hash, path: &mut Vec<String>) -> Result<Vec<(String, Node)>, Error> {
+    let mut entries = Vec::with_capacity(hash.len());
+
+    for (key, value) in hash {
+        let key = match scalar(key) {
+            Some(key) => key,
+            None => return Err(Error::Unsupported(path.join("."))),
+        };
+
+        path.push(key);
+        let node = node(value, path)?;
+        entries.push((path.pop().unwrap_or_default(), node));
+    }
+
+    Ok(entries)
+}
+
+fn node(value: Yaml, path: &mut Vec<String>) -> Result<Node, Error> {
+    Ok(match value {
+        Yaml::Integer(int) => Node::integer(int),
+        Yaml::Array(items) => {
+            let mut nodes = Vec::with_capacity(items.len());
+
+            for (index, item) in items.into_iter().enumerate() {
+                path.push(index.to_string());
+                nodes.push(node(item, path)?);
+                path.pop();
+            }
+
+            Node::Array(nodes)
+        }
+        Yaml::Hash(hash) => Node::Table(entries(hash, path)?),
+        value => match scalar(value) {
+            Some(s) => Node::string(s),
+            None => return Err(Error::Unsupported(path.join("."))),
+        },
+    })
+}
+
+/// The text of a scalar, as used for keys and values other than integers.
+fn scalar(value: Yaml) -> Option<String> {
+    match value {
+        Yaml::String(s) | Yaml::Real(s) => Some(s),
+        Yaml::Integer(int) => Some(int.to_string()),
+        Yaml::Boolean(boolean) => Some(boolean.to_string()),
+        Yaml::Null => Some(String::new()),
+        Yaml::Array(_) | Yaml::Hash(_) | Yaml::Alias(_) | Yaml::BadValue => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::import::{ArrayPolicy, Error};
+
+    fn vars(text: &str, policy: ArrayPolicy) -> Vec<(String, String)> {
+        parse(text, policy)
+            .expect("yaml should flatten")
+            .document()
+            .expect("keys should be valid")
+            .vars()
+            .collect()
+    }
+
+    #[test]
+    fn flattens() {
+        let text = "\
+name: app
+offset: -5
+ratio: 0.5
+debug: false
+missing: ~
+hosts: [a, 1]
+defaults: &defaults
+  port: 5432
+db:
+  primary: *defaults
+  replica: {port: 5433}
+8080: http
+";
+
+        let expected = [
+            ("NAME", "app"),
+            ("OFFSET", "-5"),
+            ("RATIO", "0.5"),
+            ("DEBUG", "false"),
+            ("MISSING", ""),
+            ("HOSTS", "a,1"),
+            ("DEFAULTS_PORT", "5432"),
+            ("DB_PRIMARY_PORT", "5432"),
+            ("DB_REPLICA_PORT", "5433"),
+            ("8080", "http"),
+        ];
+
+        assert_eq!(
+            vars(text, ArrayPolicy::Join),
+            expected.map(|(name, value)| (name.to_owned(), value.to_owned()))
+        );
+        assert!(vars("", ArrayPolicy::Join).is_empty());
+    }
+
+    #[test]
+    fn array_policies() {
+        let text = "hosts:\n  - a\n  - b\n";
+
+        assert_eq!(
+            vars(text, ArrayPolicy::Index),
+            [
+                ("HOSTS_0".to_owned(), "a".to_owned()),
+                ("HOSTS_1".to_owned(), "b".to_owned())
+            ]
+        );
+        assert!(matches!(
+            parse(text, ArrayPolicy::Reject),
+            Err(Error::Array(key)) if key == "hosts"
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            parse("a: [1", ArrayPolicy::Join),
+            Err(Error::Yaml(_))
+        ));
+        assert!(matches!(
+            parse("- 1\n- 2\n", ArrayPolicy::Join),
+            Err(Error::NotATable)
+        ));
+        assert!(matches!(
+            parse("db:\n  [a]: 1\n", ArrayPolicy::Join),
+            Err(Error::Unsupported(key)) if key == "db"
+        ));
+    }
+}
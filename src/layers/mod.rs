@@ -0,0 +1,231 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    load::{FileRefs, Sources},
+    Document, Error, Key, MergeStrategy,
+};
+
+/**
+Several nvee documents combined into one, such as a shared `.nvee` and a developer's `.nvee.local`.
+
+Layers are merged in the order they were added, using a [`MergeStrategy`] to decide which value wins
+when a key is set in more than one layer.
+
+```no_run
+# fn main() -> Result<(), nvee::Error> {
+use nvee::{Layers, MergeStrategy};
+
+let mut layers = Layers::new();
+layers.push_path(".nvee")?.push_optional(".nvee.local")?;
+
+let layered = layers.load(MergeStrategy::LastWins)?;
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Default)]
+pub struct Layers {
+    sources: Vec<Sources>,
+    refs: FileRefs,
+}
+
+impl Layers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty stack of layers whose files referenced by `@file` values are read as `refs` allows.
+    pub fn with_file_refs(refs: FileRefs) -> Self {
+        Self {
+            sources: Vec::new(),
+            refs,
+        }
+    }
+
+    /// Reads a file, along with the files it includes, and adds it as the topmost layer.
+    pub fn push_path(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        self.sources.push(Sources::read_with(path, self.refs)?);
+        Ok(self)
+    }
+
+    /// Like [`push_path`](Self::push_path), but does nothing if the file doesn't exist.
+    pub fn push_optional(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, Error> {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => self.push_str(path, text),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(self),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Adds a document's text as the topmost layer, as if it had been read from `path`.
+    pub fn push_str(&mut self, path: impl AsRef<Path>, text: String) -> Result<&mut Self, Error> {
+        self.sources
+            .push(Sources::from_text_with(path, text, self.refs)?);
+        Ok(self)
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.sources.iter().map(Sources::path)
+    }
+
+    /// Like [`Sources::locate`], but looks through every layer.
+    #[cfg(feature = "schema")]
+    pub fn locate(&self, violation: &crate::schema::Violation) -> Option<(&Path, usize, usize)> {
+        self.sources
+            .iter()
+            .find_map(|source| source.locate(violation))
+    }
+
+    /// Like [`Sources::locate_key`], but looks through every layer.
+    pub fn locate_key(&self, key: &Key) -> Option<(&Path, usize, usize)> {
+        self.sources
+            .iter()
+            .find_map(|source| source.locate_key(key))
+    }
+
+    /**
+    Parses every layer and merges them, bottom to top.
+
+    The merged document has the basename declared by the topmost layer that declares one.
+    Like [`Sources::document`], no basename is derived from the layers' paths;
+    [`LoadOptions::prepare_layers`](crate::LoadOptions::prepare_layers) derives one from the topmost layer's.
+    */
+    pub fn load(&self, strategy: MergeStrategy) -> Result<Layered<'_>, Error> {
+        let mut layered = Layered {
+            document: Document::new(),
+            origins: HashMap::new(),
+        };
+
+        for source in &self.sources {
+            let path = source.path();
+            let document = source.document()?;
+
+            // The merge keeps the first basename, so a declared one replaces those of the layers below.
+            if document.declares_basename() {
+                layered.document.take_basename();
+            }
+
+            let origins = &mut layered.origins;
+            layered
+                .document
+                .merge_with(document, strategy, |key| {
+                    origins.insert(key.clone(), path);
+                })
+                .map_err(|key| Error::Conflict {
+                    key: key.to_string(),
+                    first: layered
+                        .origin(&key)
+                        .map(Path::to_path_buf)
+                        .unwrap_or_default(),
+                    second: path.to_owned(),
+                })?;
+        }
+
+        Ok(layered)
+    }
+}
+
+/// The result of [loading](Layers::load) several layers: a merged document, and where each of its values came from.
+#[derive(Debug, Clone)]
+pub struct Layered<'a> {
+    document: Document<'a>,
+    origins: HashMap<Key<'a>, &'a Path>,
+}
+
+impl<'a> Layered<'a> {
+    pub fn document(&self) -> &Document<'a> {
+        &self.document
+    }
+
+    pub fn into_document(self) -> Document<'a> {
+        self.document
+    }
+
+    /// Returns the path of the layer that the final value of `key` came from.
+    pub fn origin(&self, key: &Key) -> Option<&'a Path> {
+        self.origins.get(key).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Layers;
+    use crate::{model::key::CONSTRUCTOR as key, Error, MergeStrategy, Value};
+
+    fn layers() -> Layers {
+        let mut layers = Layers::new();
+
+        for (path, text) in [
+            (".nvee", "[db]\nport = 2020\nuser = \"app\"\n"),
+            (".nvee.local", "[db]\nport = 5432\n"),
+            (".nvee.ci", "[ci]\nrunner = \"linux\"\n"),
+        ] {
+            layers
+                .push_str(path, text.to_owned())
+                .expect("layer should not include anything");
+        }
+
+        layers
+    }
+
+    #[test]
+    fn origins() {
+        let layers = layers();
+        let layered = layers
+            .load(MergeStrategy::LastWins)
+            .expect("loading should not fail");
+
+        assert_eq!(
+            layered.document().get(&key(&["db", "port"])),
+            Some(&Value::Integer(5432))
+        );
+
+        for (field, path) in [
+            (key(&["db", "port"]), ".nvee.local"),
+            (key(&["db", "user"]), ".nvee"),
+            (key(&["ci", "runner"]), ".nvee.ci"),
+        ] {
+            assert_eq!(layered.origin(&field), Some(Path::new(path)));
+        }
+    }
+
+    #[test]
+    fn basename() {
+        let mut layers = Layers::new();
+
+        for (path, text) in [
+            ("base.nvee", "@basename \"base\"\nx = 1\n"),
+            ("app.nvee", "@basename \"app\"\n"),
+            ("local.nvee", "x = 2\n"),
+        ] {
+            layers
+                .push_str(path, text.to_owned())
+                .expect("layer should not include anything");
+        }
+
+        let layered = layers
+            .load(MergeStrategy::LastWins)
+            .expect("loading should not fail");
+        assert_eq!(layered.document().basename(), Some("app"));
+    }
+
+    #[test]
+    fn conflict() {
+        let layers = layers();
+
+        match layers.load(MergeStrategy::Error) {
+            Err(Error::Conflict {
+                key: conflict,
+                first,
+                second,
+            }) => {
+                assert_eq!(conflict, "db.port");
+                assert_eq!(first, Path::new(".nvee"));
+                assert_eq!(second, Path::new(".nvee.local"));
+            }
+            other => panic!("expected a conflict, got {other:?}"),
+        }
+    }
+}
@@ -65,50 +65,235 @@ pub use model::{Document, Field, Ident, Key, Table, Value};
 
 pub mod parser;
 pub(crate) use parser::Sealed;
-pub use parser::{Parse, Parser};
+pub use parser::{Located, Parse, Parser};
 
 pub mod set;
 pub use set::Set;
 
+pub mod naming;
+pub use naming::{Case, NamingStrategy};
+
+pub mod secret;
+
+pub mod format;
+pub use format::ValueFormat;
+
+pub mod load;
+pub use load::{Basename, LoadOptions};
+
+pub mod export;
+
+pub mod import;
+
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "schema")]
+pub use schema::Schema;
+
+pub mod layers;
+pub use layers::{Layered, Layers};
+
+pub use model::document::{
+    MergeError, MergeStrategy, NameIssue, NameProblem, ProfileError, Strictness, VarCollision,
+};
+
 #[derive(Debug)]
 pub enum Error {
-    ParseDocument(model::document::ParseError),
+    ParseDocument(Located<model::document::ParseError>),
     Io(std::io::Error),
+    Conflict {
+        key: String,
+        first: std::path::PathBuf,
+        second: std::path::PathBuf,
+    },
+    File {
+        path: std::path::PathBuf,
+        source: Box<Error>,
+    },
+    /// A document includes itself, directly or indirectly. Contains the chain of includes, ending with the repeated file.
+    IncludeCycle(Vec<std::path::PathBuf>),
+    /// A basename that isn't a valid key, such as one derived from a file named `my app.nvee`.
+    Basename(String),
+    /// A field set directly under the reserved `profile` key, rather than in a profile section like `[profile.dev]`.
+    Profile(String),
+    /// A selected profile that no section of the document defines, such as a misspelled `NVEE_PROFILE`.
+    UndefinedProfile(String),
+    /// A path value that couldn't be resolved or, when [checked](LoadOptions::check_paths), doesn't exist.
+    Path {
+        key: String,
+        path: String,
+        source: std::io::Error,
+    },
+    /// A file referenced with `@file` that couldn't be read, or is larger than [`FileRefs`](load::FileRefs) allows.
+    FileRef {
+        key: String,
+        path: String,
+        source: std::io::Error,
+    },
+    /// An array with an item that contains the delimiter its items are joined with, so the variable is ambiguous.
+    Delimiter {
+        key: String,
+        delimiter: String,
+    },
+    /// A variable name rejected by the [`Strictness`] set with [`LoadOptions::strictness`].
+    VarName {
+        key: String,
+        name: String,
+        problem: NameProblem,
+    },
+    /// Two different keys that become the same variable under [`LoadOptions::naming`].
+    VarCollision {
+        first: String,
+        second: String,
+        name: String,
+    },
+    /// A document that breaks the [`Schema`] set with `LoadOptions::schema`, with every violation, located where possible.
+    #[cfg(feature = "schema")]
+    Schema(Vec<schema::LocatedViolation>),
 }
 
-impl From<model::document::ParseError> for Error {
-    fn from(value: model::document::ParseError) -> Self {
-        Self::ParseDocument(value)
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ParseDocument(err) => err.fmt(f),
+            Self::Io(err) => err.fmt(f),
+            Self::Conflict { key, first, second } => write!(
+                f,
+                "`{key}` is set in both {} and {}",
+                first.display(),
+                second.display()
+            ),
+            // Parse errors start with a line and column, which read as part of the path.
+            Self::File { path, source } => match **source {
+                Self::ParseDocument(_) => write!(f, "{}:{source}", path.display()),
+                _ => write!(f, "{}: {source}", path.display()),
+            },
+            Self::IncludeCycle(chain) => {
+                write!(f, "include cycle: ")?;
+
+                for (index, path) in chain.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, " -> ")?;
+                    }
+
+                    write!(f, "{}", path.display())?;
+                }
+
+                Ok(())
+            }
+            Self::Basename(name) => write!(f, "`{name}` isn't a valid basename"),
+            Self::Profile(key) => write!(
+                f,
+                "`{key}` is set directly under `profile`, which only holds profile sections like `[profile.dev]`"
+            ),
+            Self::UndefinedProfile(name) => write!(f, "no section defines the profile `{name}`"),
+            Self::Path { key, path, source } => write!(f, "`{key}`: path {path:?}: {source}"),
+            Self::FileRef { key, path, source } => {
+                write!(f, "`{key}`: can't read {path:?}: {source}")
+            }
+            Self::Delimiter { key, delimiter } => write!(
+                f,
+                "`{key}` has an item containing {delimiter:?}, which its items are joined with"
+            ),
+            Self::VarName { key, name, problem } => {
+                write!(f, "`{key}` becomes the variable {name}, which {problem}")
+            }
+            Self::VarCollision {
+                first,
+                second,
+                name,
+            } => write!(
+                f,
+                "`{first}` and `{second}` both become the variable {name}"
+            ),
+            #[cfg(feature = "schema")]
+            Self::Schema(violations) => {
+                write!(f, "the document doesn't follow its schema:")?;
+
+                for violation in violations {
+                    write!(f, "\n    {violation}")?;
+                }
+
+                Ok(())
+            }
+        }
     }
 }
 
-impl From<std::io::Error> for Error {
-    fn from(value: std::io::Error) -> Self {
-        Self::Io(value)
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) | Self::Path { source: err, .. } | Self::FileRef { source: err, .. } => {
+                Some(err)
+            }
+            Self::File { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
     }
 }
 
-pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<(), Error> {
-    let txt = std::fs::read_to_string(&path)?;
-    let mut doc = Document::parse_str(&txt)?;
+impl From<Located<model::document::ParseError>> for Error {
+    fn from(value: Located<model::document::ParseError>) -> Self {
+        Self::ParseDocument(value)
+    }
+}
 
-    if let Some(s) = path.as_ref().file_stem() {
-        if let Some(s) = s.to_str() {
-            doc.set_basename(s.to_owned());
+impl From<VarCollision<'_>> for Error {
+    fn from(value: VarCollision<'_>) -> Self {
+        Self::VarCollision {
+            first: value.first().to_string(),
+            second: value.second().to_string(),
+            name: value.name().to_owned(),
         }
     }
+}
 
-    #[cfg(not(target_family = "windows"))]
-    unsafe {
-        doc.set_vars();
+impl From<ProfileError<'_>> for Error {
+    fn from(value: ProfileError<'_>) -> Self {
+        match value {
+            ProfileError::Misplaced(key) => Self::Profile(key.to_string()),
+            ProfileError::Undefined(name) => Self::UndefinedProfile(name),
+        }
     }
+}
 
-    #[cfg(target_family = "windows")]
-    doc.set_vars_windows();
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/**
+Loads the file at `path`, along with the files it includes, and sets its variables.
 
-    Ok(())
+Any name the operating system accepts is allowed, as with [`Strictness::Lenient`];
+use [`LoadOptions::strictness`] to only allow portable names.
+*/
+pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+    LoadOptions::new()
+        .strictness(Strictness::Lenient)
+        .load(path)
+}
+
+/// The basename implied by a document's path: its file stem, unless the file is hidden, like `.nvee` or `.nvee.local`.
+pub(crate) fn basename(path: &std::path::Path) -> Option<&str> {
+    path.file_stem()?
+        .to_str()
+        .filter(|stem| !stem.starts_with('.'))
 }
 
+/// Loads `.nvee` from the current directory, like [`from_path`].
 pub fn dotnvee() -> Result<(), Error> {
-    return from_path(std::path::Path::new(".nvee"));
+    from_path(std::path::Path::new(".nvee"))
+}
+
+/**
+Loads every `.nvee` from the enclosing git repository down to the current directory, innermost winning.
+Outside of a repository, only the current directory's `.nvee` is loaded.
+Names are checked like [`from_path`] checks them.
+*/
+pub fn discover() -> Result<Vec<std::path::PathBuf>, Error> {
+    LoadOptions::new()
+        .strictness(Strictness::Lenient)
+        .load_discovered(&load::Discovery::new())
 }
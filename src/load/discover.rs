@@ -0,0 +1,268 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use super::FileRefs;
+use crate::{Error, Layers};
+
+/// Where [`Discovery`] stops searching parent directories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Boundary {
+    /// Search every directory up to the filesystem root.
+    Root,
+
+    /// Stop at the first directory containing `.git`, such as a repository root.
+    #[default]
+    Git,
+
+    /// Stop at the first directory whose `Cargo.toml` declares a `workspace`, with the `toml` feature.
+    #[cfg(feature = "toml")]
+    Workspace,
+}
+
+impl Boundary {
+    fn is_reached(self, dir: &Path) -> bool {
+        match self {
+            Self::Root => false,
+            Self::Git => dir.join(".git").exists(),
+            #[cfg(feature = "toml")]
+            Self::Workspace => std::fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| declares_workspace(&manifest)),
+        }
+    }
+}
+
+/// Whether a `Cargo.toml` has a `workspace` table. Manifests that aren't valid TOML don't.
+#[cfg(feature = "toml")]
+fn declares_workspace(manifest: &str) -> bool {
+    manifest
+        .parse::<toml::Table>()
+        .is_ok_and(|manifest| manifest.contains_key("workspace"))
+}
+
+/**
+Finds nvee files by searching a directory and its parents.
+
+```no_run
+# fn main() -> Result<(), nvee::Error> {
+use nvee::load::{Boundary, Discovery};
+
+// every `.nvee` from the repository root down to the current directory
+let paths = Discovery::new().boundary(Boundary::Git).find()?;
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    file_name: OsString,
+    boundary: Boundary,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self {
+            file_name: ".nvee".into(),
+            boundary: Boundary::default(),
+        }
+    }
+}
+
+impl Discovery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the files to look for, `.nvee` by default.
+    pub fn file_name(mut self, name: impl Into<OsString>) -> Self {
+        self.file_name = name.into();
+        self
+    }
+
+    pub fn boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /**
+    Searches `start` and its parents up to the boundary, which is searched too.
+
+    The found paths are ordered from outermost to innermost.
+    If any boundary but [`Root`](Boundary::Root) is never reached, only `start` itself is searched,
+    so a stray file further up, like `/.nvee`, isn't loaded outside of a repository.
+    */
+    pub fn find_from(&self, start: impl AsRef<Path>) -> Result<Vec<PathBuf>, Error> {
+        let start = std::path::absolute(start)?;
+        let mut found = Vec::new();
+        let mut reached = self.boundary == Boundary::Root;
+
+        for dir in start.ancestors() {
+            let candidate = dir.join(&self.file_name);
+
+            if candidate.is_file() {
+                found.push(candidate);
+            }
+
+            if self.boundary.is_reached(dir) {
+                reached = true;
+                break;
+            }
+        }
+
+        if !reached {
+            found.retain(|path| path.parent() == Some(start.as_path()));
+        }
+
+        found.reverse();
+        Ok(found)
+    }
+
+    /// Like [`find_from`](Self::find_from), starting at the current directory.
+    pub fn find(&self) -> Result<Vec<PathBuf>, Error> {
+        self.find_from(std::env::current_dir()?)
+    }
+
+    /// The innermost file found from the current directory, if there is one.
+    pub fn nearest(&self) -> Result<Option<PathBuf>, Error> {
+        Ok(self.find()?.pop())
+    }
+
+    /// Reads every file found from the current directory as [`Layers`], outermost at the bottom.
+    pub fn layers(&self) -> Result<Layers, Error> {
+        self.layers_with(FileRefs::DEFAULT)
+    }
+
+    /// Like [`layers`](Self::layers), but reads the files referenced by `@file` values as `refs` allows.
+    pub fn layers_with(&self, refs: FileRefs) -> Result<Layers, Error> {
+        let mut layers = Layers::with_file_refs(refs);
+
+        for path in self.find()? {
+            layers.push_path(path)?;
+        }
+
+        Ok(layers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{Boundary, Discovery};
+
+    /// Creates `repo/.git`, `repo/.nvee`, `repo/member/.nvee` and `repo/member/src` in a fresh temporary directory.
+    fn tree(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("nvee-discover-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        fs::create_dir_all(root.join("repo/.git")).unwrap();
+        fs::create_dir_all(root.join("repo/member/src")).unwrap();
+        fs::write(root.join(".nvee"), "outside = 1\n").unwrap();
+        fs::write(root.join("repo/.nvee"), "shared = 1\n").unwrap();
+        fs::write(root.join("repo/member/.nvee"), "member = 1\n").unwrap();
+        fs::write(
+            root.join("repo/Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]\n",
+        )
+        .unwrap();
+
+        root
+    }
+
+    #[test]
+    fn git_boundary() {
+        let root = tree("git");
+
+        let found = Discovery::new()
+            .find_from(root.join("repo/member/src"))
+            .expect("discovery should not fail");
+
+        assert_eq!(
+            found,
+            [root.join("repo/.nvee"), root.join("repo/member/.nvee")]
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn workspace_boundary() {
+        let root = tree("workspace");
+        fs::remove_dir_all(root.join("repo/.git")).unwrap();
+
+        let found = Discovery::new()
+            .boundary(Boundary::Workspace)
+            .find_from(root.join("repo/member"))
+            .expect("discovery should not fail");
+
+        assert_eq!(
+            found,
+            [root.join("repo/.nvee"), root.join("repo/member/.nvee")]
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn workspace_manifests() {
+        for manifest in [
+            "[workspace]\n",
+            "[ workspace ] # members below\nmembers = []\n",
+            "[package]\nname = \"app\"\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+            "workspace = { members = [\"member\"] }\n",
+            "workspace.members = [\"member\"]\n",
+        ] {
+            assert!(super::declares_workspace(manifest), "{manifest:?}");
+        }
+
+        for manifest in [
+            "[package]\nname = \"app\"\n",
+            "[package]\ndescription = \"\"\"\n[workspace]\n\"\"\"\n",
+            "# [workspace]\n",
+        ] {
+            assert!(!super::declares_workspace(manifest), "{manifest:?}");
+        }
+    }
+
+    #[test]
+    fn root_boundary() {
+        let root = tree("root");
+
+        let found = Discovery::new()
+            .boundary(Boundary::Root)
+            .find_from(root.join("repo/member/src"))
+            .expect("discovery should not fail");
+
+        assert!(found.ends_with(&[
+            root.join(".nvee"),
+            root.join("repo/.nvee"),
+            root.join("repo/member/.nvee"),
+        ]));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn unreached_boundary() {
+        let root = tree("unreached");
+        fs::remove_dir_all(root.join("repo/.git")).unwrap();
+
+        for start in ["repo/member", "repo/member/src"] {
+            let found = Discovery::new()
+                .find_from(root.join(start))
+                .expect("discovery should not fail");
+
+            let expected: &[PathBuf] = match start {
+                "repo/member" => &[root.join("repo/member/.nvee")],
+                _ => &[],
+            };
+            assert_eq!(found, expected, "{start}");
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
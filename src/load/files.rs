@@ -0,0 +1,197 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+use typed_arena::Arena;
+
+use crate::{Document, Error, Value};
+
+/**
+How the files referenced by `@file` values are read.
+
+```
+# use nvee::load::{FileRefs, Sources};
+let refs = FileRefs {
+    max_size: 1024,
+    ..FileRefs::DEFAULT
+};
+
+assert!(Sources::read_with("tests/files/app.nvee", refs).is_ok());
+assert!(Sources::read_with("tests/files/app.nvee", FileRefs { max_size: 4, ..refs }).is_err());
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileRefs {
+    /// The largest file, in bytes, that can be referenced. Larger files fail with [`Error::FileRef`].
+    pub max_size: u64,
+    /// Whether a single trailing newline, as most editors add, is removed from the contents.
+    pub trim_newline: bool,
+}
+
+impl FileRefs {
+    pub const DEFAULT: Self = Self {
+        max_size: 64 * 1024,
+        trim_newline: true,
+    };
+}
+
+impl Default for FileRefs {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Calls `f` with every file reference in `value`, including those in arrays.
+fn for_each_file<'a>(
+    value: &mut Value<'a>,
+    f: &mut impl FnMut(&mut Value<'a>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match value {
+        Value::File(_) => f(value),
+        Value::Array(items) => items.iter_mut().try_for_each(|item| for_each_file(item, f)),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the file at `path`, failing if it's larger than `refs` allows.
+fn read(path: &str, refs: FileRefs) -> io::Result<String> {
+    let mut contents = String::new();
+
+    File::open(path)?
+        .take(refs.max_size.saturating_add(1))
+        .read_to_string(&mut contents)?;
+
+    if contents.len() as u64 > refs.max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::FileTooLarge,
+            format!("the file is larger than {} bytes", refs.max_size),
+        ));
+    }
+
+    Ok(contents)
+}
+
+/**
+Replaces every file reference in `document`, which was read from a file in `dir`, with the referenced file's contents.
+
+Files are read once, into `arena`; `contents` holds what was already read, keyed by resolved path.
+*/
+pub(crate) fn substitute<'a>(
+    document: &mut Document<'a>,
+    dir: &Path,
+    refs: FileRefs,
+    arena: &'a Arena<String>,
+    contents: &mut HashMap<String, &'a str>,
+) -> Result<(), Error> {
+    for (key, value) in document.values_mut() {
+        for_each_file(value, &mut |value| {
+            let Value::File(path) = value else {
+                return Ok(());
+            };
+
+            let error = |source| Error::FileRef {
+                key: key.to_string(),
+                path: path.to_string(),
+                source,
+            };
+
+            let resolved = super::paths::resolve(path, dir).map_err(error)?;
+
+            let text = match contents.entry(resolved) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let read = read(entry.key(), refs).map_err(error)?;
+                    *entry.insert(arena.alloc(read))
+                }
+            };
+
+            let text = match text.strip_suffix('\n') {
+                Some(line) if refs.trim_newline => line.strip_suffix('\r').unwrap_or(line),
+                _ => text,
+            };
+
+            *value = Value::String(text);
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::FileRefs;
+    use crate::{load::Sources, Error, LoadOptions};
+
+    fn load(text: &str, refs: FileRefs) -> Result<Vec<String>, Error> {
+        let sources = Sources::from_text_with("tests/files/app.nvee", text.to_owned(), refs)?;
+        let options = LoadOptions::new();
+        let doc = options.prepare(&sources)?;
+
+        Ok(doc.vars().map(|(_, value)| value).collect())
+    }
+
+    #[test]
+    fn contents() {
+        let text = "password = @file(\"secrets/db_password\")\nkeys = [@file(\"secrets/db_password\"), \"b\"]\n";
+
+        assert_eq!(
+            load(text, FileRefs::DEFAULT).expect("secrets should be read"),
+            ["hunter2", "hunter2,b"]
+        );
+
+        let untrimmed = FileRefs {
+            trim_newline: false,
+            ..FileRefs::DEFAULT
+        };
+        assert_eq!(
+            load(text, untrimmed).expect("secrets should be read"),
+            ["hunter2\n", "hunter2\n,b"]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let missing = load("password = @file(\"nowhere\")", FileRefs::DEFAULT);
+        let large = load(
+            "password = @file(\"secrets/db_password\")",
+            FileRefs {
+                max_size: 4,
+                ..FileRefs::DEFAULT
+            },
+        );
+
+        for (result, kind) in [
+            (missing, io::ErrorKind::NotFound),
+            (large, io::ErrorKind::FileTooLarge),
+        ] {
+            match result {
+                Err(Error::File { source, .. }) => assert!(matches!(
+                    *source,
+                    Error::FileRef { key, source, .. } if key == "password" && source.kind() == kind
+                )),
+                other => panic!("expected a file reference error, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn layers() {
+        let options = LoadOptions::new().file_refs(FileRefs {
+            max_size: 4,
+            ..FileRefs::DEFAULT
+        });
+
+        assert!(options.layers().push_path("tests/files/app.nvee").is_err());
+        assert!(LoadOptions::new()
+            .layers()
+            .push_path("tests/files/app.nvee")
+            .is_ok());
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use self_cell::self_cell;
+use typed_arena::Arena;
+
+use super::FileRefs;
+use crate::{Document, Error, Key, MergeStrategy, Parse};
+
+/**
+A document's text along with the text of every document it `@include`s, directly or indirectly.
+
+Include paths are resolved relative to the directory of the including file.
+An included document's keys are nested under its basename: the one it declares with `@basename`, or else its file stem,
+the same way [`from_path`](crate::from_path) names variables: `@include "db.nvee"` turns `url` in `db.nvee` into `db.url`.
+
+Every file is parsed once, when it's read. The files referenced by `@file` values are read along with the documents,
+as [`FileRefs`] allows.
+*/
+pub struct Sources {
+    files: Files,
+}
+
+self_cell!(
+    /// The text of every file, and the documents parsed from it.
+    struct Files {
+        owner: Arena<String>,
+
+        #[covariant]
+        dependent: SourceFiles,
+    }
+);
+
+type SourceFiles<'a> = Vec<SourceFile<'a>>;
+
+#[derive(Debug)]
+struct SourceFile<'a> {
+    path: PathBuf,
+    /// The file's text, which keys and schema violations are located in.
+    text: &'a str,
+    /// The parsed document, with file references substituted and paths resolved, but nothing included yet.
+    document: Document<'a>,
+    includes: Vec<usize>,
+}
+
+impl fmt::Debug for Sources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sources")
+            .field("files", self.files.borrow_dependent())
+            .finish()
+    }
+}
+
+/// Reads and parses files into an arena, following includes.
+struct Reader<'a> {
+    arena: &'a Arena<String>,
+    refs: FileRefs,
+    files: SourceFiles<'a>,
+    /// The contents of every file referenced with `@file`, by resolved path.
+    contents: HashMap<String, &'a str>,
+    /// The index of every file read so far, by canonical path.
+    visited: HashMap<PathBuf, usize>,
+}
+
+impl Sources {
+    /// Reads the file at `path` and everything it includes.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::read_with(path, FileRefs::DEFAULT)
+    }
+
+    /// Like [`read`](Self::read), but reads referenced files as `refs` allows.
+    pub fn read_with(path: impl AsRef<Path>, refs: FileRefs) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(&path).map_err(|err| Error::File {
+            path: path.as_ref().to_owned(),
+            source: Box::new(err.into()),
+        })?;
+
+        Self::from_text_with(path, text, refs)
+    }
+
+    /// Reads everything included by `text`, as if it had been read from `path`.
+    pub fn from_text(path: impl AsRef<Path>, text: String) -> Result<Self, Error> {
+        Self::from_text_with(path, text, FileRefs::DEFAULT)
+    }
+
+    /// Like [`from_text`](Self::from_text), but reads referenced files as `refs` allows.
+    pub fn from_text_with(
+        path: impl AsRef<Path>,
+        text: String,
+        refs: FileRefs,
+    ) -> Result<Self, Error> {
+        let files = Files::try_new(Arena::new(), |arena| {
+            let mut reader = Reader {
+                arena,
+                refs,
+                files: Vec::new(),
+                contents: HashMap::new(),
+                visited: HashMap::new(),
+            };
+
+            reader.add(path.as_ref().to_owned(), text, &mut Vec::new())?;
+
+            Ok::<_, Error>(reader.files)
+        })?;
+
+        Ok(Self { files })
+    }
+
+    fn files(&self) -> &[SourceFile<'_>] {
+        self.files.borrow_dependent()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.files()[0].path
+    }
+
+    /// Finds the file, line and column of a violation in the document built from these sources, if it has a location.
+    #[cfg(feature = "schema")]
+    pub fn locate(&self, violation: &crate::schema::Violation) -> Option<(&Path, usize, usize)> {
+        self.files().iter().find_map(|file| {
+            let (line, column) = violation.locate(file.text)?;
+            Some((file.path.as_path(), line, column))
+        })
+    }
+
+    /// Finds the file, line and column where the last segment of `key`, from the document built from these sources, was written.
+    pub fn locate_key(&self, key: &Key) -> Option<(&Path, usize, usize)> {
+        self.files().iter().find_map(|file| {
+            let (line, column) = key.last_segment().locate(file.text)?;
+            Some((file.path.as_path(), line, column))
+        })
+    }
+
+    /// Builds the root document and splices in everything it includes.
+    pub fn document(&self) -> Result<Document<'_>, Error> {
+        self.document_at(0)
+    }
+
+    fn document_at(&self, index: usize) -> Result<Document<'_>, Error> {
+        let files = self.files();
+        let file = &files[index];
+        let in_file = |err: Error| Error::File {
+            path: file.path.clone(),
+            source: Box::new(err),
+        };
+
+        let mut document = file.document.clone();
+
+        for &included in &file.includes {
+            let path = &files[included].path;
+            let mut spliced = self.document_at(included).map_err(in_file)?;
+
+            let declared = spliced.declares_basename();
+            let prefix = match spliced.take_basename() {
+                // Declared basenames are parsed, so they borrow the included file's text.
+                Some(Cow::Borrowed(name)) if declared => Some(name),
+                _ => crate::basename(path),
+            };
+
+            let nested = match prefix {
+                Some(prefix) => {
+                    let prefix = Key::parse_whole(prefix)
+                        .ok_or_else(|| in_file(Error::Basename(prefix.to_owned())))?;
+                    spliced.nest(&prefix)
+                }
+                None => spliced,
+            };
+
+            document
+                .merge_with(nested, MergeStrategy::Error, |_| ())
+                .map_err(|key| {
+                    in_file(Error::Conflict {
+                        key: key.to_string(),
+                        first: file.path.clone(),
+                        second: path.clone(),
+                    })
+                })?;
+        }
+
+        Ok(document)
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Reads a file and, recursively, its includes, returning the file's index.
+    fn add(
+        &mut self,
+        path: PathBuf,
+        text: String,
+        chain: &mut Vec<PathBuf>,
+    ) -> Result<usize, Error> {
+        let in_file = |err: Error| Error::File {
+            path: path.clone(),
+            source: Box::new(err),
+        };
+
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+
+        if chain.contains(&canonical) {
+            chain.push(canonical);
+            return Err(Error::IncludeCycle(std::mem::take(chain)));
+        }
+
+        if let Some(&index) = self.visited.get(&canonical) {
+            return Ok(index);
+        }
+
+        let text: &'a str = self.arena.alloc(text);
+        let dir = path.parent().unwrap_or(Path::new(""));
+
+        let mut document = Document::parse_located(text).map_err(|err| in_file(err.into()))?;
+        super::files::substitute(
+            &mut document,
+            dir,
+            self.refs,
+            self.arena,
+            &mut self.contents,
+        )
+        .map_err(in_file)?;
+        super::paths::resolve_all(&mut document, dir).map_err(in_file)?;
+
+        let include_paths: Vec<PathBuf> = document
+            .includes()
+            .map(|include| dir.join(include))
+            .collect();
+
+        let index = self.files.len();
+        self.files.push(SourceFile {
+            path: path.clone(),
+            text,
+            document,
+            includes: Vec::new(),
+        });
+
+        chain.push(canonical.clone());
+
+        for include in include_paths {
+            let text = std::fs::read_to_string(&include).map_err(|err| {
+                in_file(Error::File {
+                    path: include.clone(),
+                    source: Box::new(err.into()),
+                })
+            })?;
+
+            let included = self.add(include, text, chain).map_err(|err| match err {
+                Error::IncludeCycle(_) => err,
+                err => in_file(err),
+            })?;
+
+            self.files[index].includes.push(included);
+        }
+
+        chain.pop();
+        self.visited.insert(canonical, index);
+
+        Ok(index)
+    }
+}
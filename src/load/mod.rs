@@ -0,0 +1,584 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    Document, Error, Key, Layers, MergeStrategy, NameIssue, NamingStrategy, Strictness, ValueFormat,
+};
+
+mod discover;
+pub use discover::{Boundary, Discovery};
+
+mod files;
+pub use files::FileRefs;
+
+mod include;
+pub use include::Sources;
+
+mod paths;
+
+/**
+Where a loaded document's basename, the name prepended to its variables, comes from.
+
+A basename declared in the document with `@basename "name"` takes precedence over one derived
+from its path, so renaming the file doesn't rename its variables. An [`Explicit`](Self::Explicit)
+basename or [`Disabled`](Self::Disabled) overrides the declared one.
+
+```
+# use nvee::{load::Sources, Basename, LoadOptions};
+let sources = Sources::from_text("config/db.nvee", "port = 2020\n".to_owned()).unwrap();
+
+let stem = LoadOptions::new();
+assert_eq!(stem.prepare(&sources).unwrap().basename(), Some("db"));
+
+let explicit = LoadOptions::new().basename(Basename::Explicit("app".to_owned()));
+assert_eq!(explicit.prepare(&sources).unwrap().basename(), Some("app"));
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Basename {
+    /// The file's stem, like `db` for `db.nvee`. Hidden files, like `.nvee`, have none.
+    #[default]
+    FileStem,
+
+    /// The name of the directory containing the file.
+    ParentDir,
+
+    Explicit(String),
+
+    /// No basename, even if the document declares one.
+    Disabled,
+}
+
+impl Basename {
+    /// Sets the basename of `doc`, which was read from `path`, and checks that it's a valid key.
+    fn apply<'a>(&self, doc: &mut Document<'a>, path: &'a Path) -> Result<(), Error> {
+        match self {
+            Self::Disabled => {
+                doc.take_basename();
+            }
+            Self::Explicit(name) => doc.set_basename(name.clone()),
+            _ if doc.declares_basename() => {}
+            Self::FileStem => {
+                if let Some(stem) = crate::basename(path) {
+                    doc.set_basename(stem);
+                }
+            }
+            Self::ParentDir => {
+                let dir = path.canonicalize().or_else(|_| std::path::absolute(path))?;
+                let name = dir
+                    .parent()
+                    .and_then(Path::file_name)
+                    .and_then(|name| name.to_str());
+
+                if let Some(name) = name {
+                    doc.set_basename(name.to_owned());
+                }
+            }
+        }
+
+        match doc.basename() {
+            Some(name) if Key::parse_whole(name).is_none() => Err(Error::Basename(name.to_owned())),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The callback set with [`LoadOptions::on_warning`].
+#[derive(Clone)]
+struct OnWarning(Arc<dyn Fn(&NameIssue) + Send + Sync>);
+
+impl fmt::Debug for OnWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnWarning(..)")
+    }
+}
+
+/**
+Options for loading nvee files into the environment.
+
+```no_run
+# fn main() -> Result<(), nvee::Error> {
+nvee::LoadOptions::new().profile("dev").load(".nvee")?;
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    profile: Option<String>,
+    basename: Basename,
+    naming: NamingStrategy,
+    format: ValueFormat,
+    strictness: Strictness,
+    on_warning: Option<OnWarning>,
+    check_paths: bool,
+    file_refs: FileRefs,
+    #[cfg(feature = "schema")]
+    schema: Option<crate::Schema>,
+}
+
+impl LoadOptions {
+    /// The environment variable read to select a profile, if none is set with [`profile`](Self::profile).
+    pub const PROFILE_VAR: &'static str = "NVEE_PROFILE";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the [profile](Document::select_profile) to apply, overriding [`NVEE_PROFILE`](Self::PROFILE_VAR).
+    pub fn profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Sets where the basename comes from, the file stem by default.
+    pub fn basename(mut self, basename: Basename) -> Self {
+        self.basename = basename;
+        self
+    }
+
+    /// Sets how keys are turned into variable names, [`NamingStrategy::DEFAULT`] by default.
+    pub fn naming(mut self, naming: NamingStrategy) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Sets how values are written as variables, [`ValueFormat::DEFAULT`] by default.
+    pub fn format(mut self, format: ValueFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /**
+    Sets how variable names are checked with [`Document::check_names`] before any are set,
+    failing with [`Error::VarName`] on the first name `strictness` rejects.
+
+    Names are checked as [`Strictness::Portable`] by default; [`Strictness::Lenient`] accepts any name
+    the operating system does. Warnings, like names shadowing `PATH`, never fail loading;
+    see [`on_warning`](Self::on_warning).
+    */
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /**
+    Calls `f` with every [warning](crate::NameProblem::is_warning) about variable names, like names shadowing `PATH`,
+    found while expanding a document. Warnings are dropped by default.
+
+    ```
+    # use std::sync::{Arc, Mutex};
+    # use nvee::{Document, LoadOptions, Parse};
+    let warnings = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&warnings);
+    let options = LoadOptions::new().on_warning(move |issue| sink.lock().unwrap().push(issue.to_string()));
+
+    let doc = Document::parse_str("path = \"/bin\"\n").unwrap();
+    options.vars(doc).unwrap();
+
+    assert_eq!(
+        *warnings.lock().unwrap(),
+        ["`path` becomes the variable PATH, which overrides the well-known variable PATH"]
+    );
+    ```
+    */
+    pub fn on_warning(mut self, f: impl Fn(&NameIssue) + Send + Sync + 'static) -> Self {
+        self.on_warning = Some(OnWarning(Arc::new(f)));
+        self
+    }
+
+    /// Sets whether every `@path` value must exist, failing with [`Error::Path`] otherwise. They needn't by default.
+    pub fn check_paths(mut self, check: bool) -> Self {
+        self.check_paths = check;
+        self
+    }
+
+    /// Sets how the files referenced by `@file` values are read, [`FileRefs::DEFAULT`] by default.
+    pub fn file_refs(mut self, refs: FileRefs) -> Self {
+        self.file_refs = refs;
+        self
+    }
+
+    /**
+    Validates documents against `schema` as they're prepared, failing with [`Error::Schema`] on any violation,
+    then sets the schema's defaults for keys that aren't set.
+    */
+    #[cfg(feature = "schema")]
+    pub fn schema(mut self, schema: crate::Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn naming_strategy(&self) -> &NamingStrategy {
+        &self.naming
+    }
+
+    pub fn value_format(&self) -> &ValueFormat {
+        &self.format
+    }
+
+    pub fn name_strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    /// An empty stack of layers that reads files the way these options do, to [prepare](Self::prepare_layers) later.
+    pub fn layers(&self) -> Layers {
+        Layers::with_file_refs(self.file_refs)
+    }
+
+    /// The profile to apply: the one set on these options, or else the one named by [`NVEE_PROFILE`](Self::PROFILE_VAR).
+    pub fn selected_profile(&self) -> Option<String> {
+        self.profile
+            .clone()
+            .or_else(|| std::env::var(Self::PROFILE_VAR).ok())
+    }
+
+    /// Builds a document from its sources and prepares it for expansion according to these options.
+    pub fn prepare<'a>(&'a self, sources: &'a Sources) -> Result<Document<'a>, Error> {
+        let mut doc = sources.document()?;
+
+        self.basename.apply(&mut doc, sources.path())?;
+        doc.select_profile(self.selected_profile().as_deref())?;
+
+        if self.check_paths {
+            paths::check_exist(&mut doc)?;
+        }
+
+        #[cfg(feature = "schema")]
+        self.validate(&doc, |violation| sources.locate(violation))?;
+
+        Ok(self.with_defaults(doc))
+    }
+
+    /// Fails with [`Error::Schema`] if `doc` breaks the set [`schema`](Self::schema), locating violations with `locate`.
+    #[cfg(feature = "schema")]
+    fn validate<'p>(
+        &self,
+        doc: &Document,
+        locate: impl Fn(&crate::schema::Violation) -> Option<(&'p Path, usize, usize)>,
+    ) -> Result<(), Error> {
+        let Some(schema) = &self.schema else {
+            return Ok(());
+        };
+
+        doc.validate(schema).map_err(|violations| {
+            let located = violations.iter().map(|violation| {
+                crate::schema::LocatedViolation::new(violation, locate(violation))
+            });
+
+            Error::Schema(located.collect())
+        })
+    }
+
+    /// Sets the defaults of the set [`schema`](Self::schema), if there is one.
+    fn with_defaults<'a>(&'a self, doc: Document<'a>) -> Document<'a> {
+        #[cfg(feature = "schema")]
+        if let Some(schema) = &self.schema {
+            let mut doc = doc;
+            doc.fill_defaults(schema);
+            return doc;
+        }
+
+        doc
+    }
+
+    /**
+    Fails with [`Error::VarCollision`] if two keys of `doc` become the same variable under the set [naming](Self::naming),
+    or with [`Error::VarName`] on the first variable name that the set [`strictness`](Self::strictness) rejects.
+    Otherwise, passes any warnings to the [callback](Self::on_warning).
+    */
+    fn check_names(&self, doc: &Document) -> Result<(), Error> {
+        doc.check_collisions(&self.naming)?;
+
+        let issues = doc.check_names(&self.naming, self.strictness);
+
+        if let Some(issue) = issues.iter().find(|issue| !issue.problem().is_warning()) {
+            return Err(Error::VarName {
+                key: issue.key().to_string(),
+                name: issue.name().to_owned(),
+                problem: issue.problem(),
+            });
+        }
+
+        if let Some(OnWarning(f)) = &self.on_warning {
+            issues.iter().for_each(|issue| f(issue));
+        }
+
+        Ok(())
+    }
+
+    /**
+    Expands `doc` into variables named by these options, without setting them.
+
+    This is the safe way to pass a document to a child process, with [`Command::envs`](std::process::Command::envs).
+    Defaults from the [schema](Self::schema), if one is set, are expanded along with the document's own values
+    when `doc` was [prepared](Self::prepare) by these options.
+    */
+    pub fn vars(&self, doc: Document) -> Result<Vec<(String, String)>, Error> {
+        self.check_names(&doc)?;
+
+        if let Some(key) = doc.find_ambiguous_array(&self.format) {
+            let delimiter = self.format.delimiter_for(*key.last_segment());
+
+            return Err(Error::Delimiter {
+                key: key.to_string(),
+                delimiter: delimiter.to_owned(),
+            });
+        }
+
+        Ok(doc.vars_with_format(&self.naming, &self.format).collect())
+    }
+
+    /// Sets the variables of `doc`, leaving those that are already set alone, like [`Document::set_vars`].
+    fn set_vars(&self, doc: Document) -> Result<(), Error> {
+        for (name, value) in self.vars(doc)? {
+            if std::env::var_os(&name).is_none() {
+                // See `Document::set_vars` for when this is sound.
+                unsafe { std::env::set_var(name, value) }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the file at `path`, along with the files it includes, and sets its variables.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let sources = Sources::read_with(path, self.file_refs)?;
+        let doc = self.prepare(&sources)?;
+
+        self.set_vars(doc)?;
+
+        Ok(())
+    }
+
+    /**
+    Loads every file found by `discovery` and sets their variables.
+
+    Files closer to the current directory override the ones further up.
+    Returns the paths of the loaded files, from outermost to innermost.
+    */
+    pub fn load_discovered(&self, discovery: &Discovery) -> Result<Vec<PathBuf>, Error> {
+        let layers = discovery.layers_with(self.file_refs)?;
+        let doc = self.prepare_layers(&layers)?;
+
+        self.set_vars(doc)?;
+
+        Ok(layers.paths().map(Path::to_path_buf).collect())
+    }
+
+    /**
+    Merges `layers`, later ones winning, and prepares the result like [`prepare`](Self::prepare).
+
+    The basename is derived from the topmost layer's path.
+    */
+    pub fn prepare_layers<'a>(&'a self, layers: &'a Layers) -> Result<Document<'a>, Error> {
+        let mut doc = layers.load(MergeStrategy::LastWins)?.into_document();
+
+        if let Some(topmost) = layers.paths().last() {
+            self.basename.apply(&mut doc, topmost)?;
+        }
+
+        doc.select_profile(self.selected_profile().as_deref())?;
+
+        if self.check_paths {
+            paths::check_exist(&mut doc)?;
+        }
+
+        #[cfg(feature = "schema")]
+        self.validate(&doc, |violation| layers.locate(violation))?;
+
+        Ok(self.with_defaults(doc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Basename, LoadOptions, Sources};
+    use crate::Error;
+
+    fn basename(path: &str, text: &str, basename: Basename) -> Result<Option<String>, Error> {
+        let sources = Sources::from_text(path, text.to_owned())?;
+        let options = LoadOptions::new().basename(basename);
+        let doc = options.prepare(&sources)?;
+
+        Ok(doc.basename().map(str::to_owned))
+    }
+
+    #[test]
+    fn basenames() {
+        let declared = "@basename \"app\"\nport = 2020\n";
+
+        for (path, text, option, expected) in [
+            ("db.nvee", "", Basename::FileStem, Some("db")),
+            ("my-app.nvee", "", Basename::FileStem, Some("my-app")),
+            (".nvee", "", Basename::FileStem, None),
+            ("config/.nvee.local", "", Basename::FileStem, None),
+            ("config/.nvee", "", Basename::ParentDir, Some("config")),
+            ("renamed.nvee", declared, Basename::FileStem, Some("app")),
+            ("config/.nvee", declared, Basename::ParentDir, Some("app")),
+            (
+                "db.nvee",
+                declared,
+                Basename::Explicit("db".to_owned()),
+                Some("db"),
+            ),
+            ("db.nvee", declared, Basename::Disabled, None),
+        ] {
+            assert_eq!(
+                basename(path, text, option).expect("basename should be valid"),
+                expected.map(str::to_owned),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn layer_basenames() {
+        use crate::Layers;
+
+        let mut layers = Layers::new();
+        layers
+            .push_str("base.nvee", "x = 1\n".to_owned())
+            .and_then(|layers| layers.push_str("local.nvee", "y = 2\n".to_owned()))
+            .expect("layers should not include anything");
+
+        let options = LoadOptions::new();
+        let doc = options.prepare_layers(&layers).expect("layers should load");
+        assert_eq!(
+            options.vars(doc).expect("names should be valid"),
+            [
+                ("LOCAL_X".to_owned(), "1".to_owned()),
+                ("LOCAL_Y".to_owned(), "2".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn strictness() {
+        use crate::{Document, NameProblem, Parse, Strictness};
+
+        let doc = || Document::parse_str("2kv = 1\n").expect("parsing should not fail");
+
+        assert!(matches!(
+            LoadOptions::new().set_vars(doc()),
+            Err(Error::VarName {
+                problem: NameProblem::LeadingDigit,
+                ..
+            })
+        ));
+        assert_eq!(
+            LoadOptions::new()
+                .strictness(Strictness::Lenient)
+                .vars(doc())
+                .expect("lenient names should pass"),
+            [("2KV".to_owned(), "1".to_owned())]
+        );
+    }
+
+    #[test]
+    fn ambiguous_arrays() {
+        use crate::{Document, Parse, ValueFormat};
+
+        let text = "hosts = [\"a,b\", \"c\"]\n";
+        let doc = || Document::parse_str(text).expect("parsing should not fail");
+
+        assert!(matches!(
+            LoadOptions::new().vars(doc()),
+            Err(Error::Delimiter { key, delimiter }) if key == "hosts" && delimiter == ","
+        ));
+        assert_eq!(
+            LoadOptions::new()
+                .format(ValueFormat::new().delimiter(";"))
+                .vars(doc())
+                .expect("items should be unambiguous"),
+            [("HOSTS".to_owned(), "a,b;c".to_owned())]
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        use crate::{Case, Document, NamingStrategy, Parse};
+
+        let text = "apiKey = 1\napikey = 2\n";
+        let doc = || Document::parse_str(text).expect("parsing should not fail");
+
+        assert!(matches!(
+            LoadOptions::new().vars(doc()),
+            Err(Error::VarCollision { name, .. }) if name == "APIKEY"
+        ));
+        assert_eq!(
+            LoadOptions::new()
+                .naming(NamingStrategy::new().case(Case::Preserve))
+                .vars(doc())
+                .expect("names should be distinct"),
+            [
+                ("apiKey".to_owned(), "1".to_owned()),
+                ("apikey".to_owned(), "2".to_owned()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn schema() {
+        use std::path::Path;
+
+        use crate::{schema::Problem, Schema};
+
+        let schema = Schema::parse("[port]\nmax = 65535\n\n[host]\ndefault = \"localhost\"\n")
+            .expect("schema should be valid");
+        let options = LoadOptions::new().schema(schema);
+
+        let invalid = Sources::from_text("app.nvee", "port = 70000\n".to_owned())
+            .expect("sources should parse");
+        let err = options
+            .prepare(&invalid)
+            .expect_err("document should be invalid");
+        assert_eq!(
+            err.to_string(),
+            "the document doesn't follow its schema:\n    app.nvee:1:1: `port` must be at most 65535"
+        );
+
+        let Error::Schema(violations) = err else {
+            panic!("should be a schema error: {err:?}");
+        };
+        assert_eq!(violations[0].key(), "port");
+        assert_eq!(violations[0].problem(), &Problem::Above(65535));
+        assert_eq!(
+            violations[0].location(),
+            Some((Path::new("app.nvee"), 1, 1))
+        );
+
+        let valid = Sources::from_text("app.nvee", "port = 2020\n".to_owned())
+            .expect("sources should parse");
+        let doc = options.prepare(&valid).expect("document should be valid");
+        assert_eq!(
+            doc.fields().count(),
+            2,
+            "defaults should be set by `prepare`"
+        );
+        assert_eq!(
+            options.vars(doc).expect("names should be valid"),
+            [
+                ("APP_PORT".to_owned(), "2020".to_owned()),
+                ("APP_HOST".to_owned(), "localhost".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_basenames() {
+        for (path, option) in [
+            ("my app.nvee", Basename::FileStem),
+            ("db.nvee", Basename::Explicit("db.".to_owned())),
+        ] {
+            assert!(matches!(
+                basename(path, "", option),
+                Err(Error::Basename(_))
+            ));
+        }
+    }
+}
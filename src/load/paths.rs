@@ -0,0 +1,144 @@
+use std::{
+    borrow::Cow,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Document, Error, Value};
+
+/// Resolves the text of a path value against `dir`, expanding a leading `~` to the home directory.
+pub(crate) fn resolve(path: &str, dir: &Path) -> io::Result<String> {
+    let expanded = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            let home = std::env::home_dir().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "the home directory is unknown")
+            })?;
+
+            match rest.trim_start_matches(['/', '\\']) {
+                "" => home,
+                rest => home.join(rest),
+            }
+        }
+        _ => PathBuf::from(path),
+    };
+
+    std::path::absolute(dir.join(expanded))?
+        .into_os_string()
+        .into_string()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "the path isn't valid UTF-8"))
+}
+
+/// Calls `f` with every path in `value`, including those in arrays.
+fn for_each_path<'a>(
+    value: &mut Value<'a>,
+    f: &mut impl FnMut(&mut Cow<'a, str>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    match value {
+        Value::Path(path) => f(path),
+        Value::Array(items) => items.iter_mut().try_for_each(|item| for_each_path(item, f)),
+        _ => Ok(()),
+    }
+}
+
+/// Resolves every path value in `document`, which was read from a file in `dir`.
+pub(crate) fn resolve_all(document: &mut Document, dir: &Path) -> Result<(), Error> {
+    for (key, value) in document.values_mut() {
+        for_each_path(value, &mut |path| {
+            *path = Cow::Owned(resolve(path, dir).map_err(|source| Error::Path {
+                key: key.to_string(),
+                path: path.to_string(),
+                source,
+            })?);
+
+            Ok(())
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Checks that every path value in `document` exists.
+pub(crate) fn check_exist(document: &mut Document) -> Result<(), Error> {
+    for (key, value) in document.values_mut() {
+        for_each_path(value, &mut |path| {
+            std::fs::metadata(&**path)
+                .map(|_| ())
+                .map_err(|source| Error::Path {
+                    key: key.to_string(),
+                    path: path.to_string(),
+                    source,
+                })
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::resolve;
+    use crate::{load::Sources, Error, LoadOptions};
+
+    #[test]
+    fn resolves() {
+        let cwd = std::env::current_dir().expect("current directory should be known");
+        let home = Path::new("/home/nvee");
+
+        temp_env::with_var("HOME", Some(home), || {
+            for (path, dir, expected) in [
+                ("./data", "config", cwd.join("config/data")),
+                ("data", "", cwd.join("data")),
+                ("/srv/data", "config", "/srv/data".into()),
+                ("~/data", "config", home.join("data")),
+                ("~", "config", home.to_owned()),
+                ("~user/data", "config", cwd.join("config/~user/data")),
+            ] {
+                assert_eq!(
+                    resolve(path, Path::new(dir)).expect("path should resolve"),
+                    expected.to_str().expect("path should be UTF-8"),
+                    "{path}"
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn load() {
+        let text = "data = @path(\"data\")\ndirs = [@path(\"src\"), \"/bin\"]\n";
+        let sources =
+            Sources::from_text("config/app.nvee", text.to_owned()).expect("nothing is included");
+        let options = LoadOptions::new();
+        let doc = options.prepare(&sources).expect("paths should resolve");
+
+        let config = std::env::current_dir()
+            .expect("current directory should be known")
+            .join("config");
+        let config = config.to_str().expect("path should be UTF-8");
+
+        assert_eq!(
+            doc.vars().map(|(_, value)| value).collect::<Vec<_>>(),
+            [format!("{config}/data"), format!("{config}/src,/bin")]
+        );
+    }
+
+    #[test]
+    fn check_exist() {
+        let check = |text: &str| {
+            let sources =
+                Sources::from_text("tests/app.nvee", text.to_owned()).expect("nothing is included");
+
+            LoadOptions::new()
+                .check_paths(true)
+                .prepare(&sources)
+                .map(|doc| doc.fields().count())
+        };
+
+        assert!(check("fixtures = @path(\"includes\")").is_ok());
+        assert!(matches!(
+            check("missing = @path(\"nowhere\")"),
+            Err(Error::Path { key, .. }) if key == "missing"
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Field, Key, Table};
+
+use super::Document;
+
+/// How [`Document::merge`] resolves a key that is set in both documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// The value from the document being merged in replaces the existing one.
+    #[default]
+    LastWins,
+
+    /// The existing value is kept.
+    FirstWins,
+
+    /// Merging fails with [`MergeError::Conflict`].
+    Error,
+}
+
+#[derive(Debug)]
+pub enum MergeError {
+    Conflict(String),
+}
+
+impl<'a> Document<'a> {
+    /**
+    Merges another document into this one, resolving keys set in both according to `strategy`.
+
+    Tables from `other` are added to this document if it doesn't have them yet.
+    This document's basename is kept; `other`'s is only used if this document has none.
+
+    ```
+    # use nvee::{Document, Key, Parse, Value, MergeStrategy};
+    let mut shared = Document::parse_str("[db]\nport = 2020\nuser = \"app\"\n").unwrap();
+    let local = Document::parse_str("[db]\nport = 5432\n").unwrap();
+
+    shared.merge(local, MergeStrategy::LastWins).unwrap();
+
+    let port = Key::from_segments(["db", "port"]).unwrap();
+    assert_eq!(shared.get(&port), Some(&Value::Integer(5432)));
+    ```
+    */
+    pub fn merge(&mut self, other: Self, strategy: MergeStrategy) -> Result<(), MergeError> {
+        self.merge_with(other, strategy, |_| ())
+            .map_err(|key| MergeError::Conflict(key.to_string()))
+    }
+
+    /**
+    Like [`merge`](Self::merge), but calls `on_set` with the full key of every value taken from `other`.
+
+    On a conflict, returns the conflicting key.
+    */
+    pub(crate) fn merge_with(
+        &mut self,
+        other: Self,
+        strategy: MergeStrategy,
+        mut on_set: impl FnMut(&Key<'a>),
+    ) -> Result<(), Key<'a>> {
+        let Self {
+            basename,
+            basename_declared,
+            fields,
+            tables,
+            ..
+        } = other;
+
+        if self.basename.is_none() && basename.is_some() {
+            self.basename = basename;
+            self.basename_declared = basename_declared;
+        }
+
+        for field in fields {
+            self.merge_field(field, strategy, &mut on_set)?;
+        }
+
+        for table in tables {
+            if self.table(table.name()).is_none() {
+                self.insert_table(Table::new(table.name().clone()));
+            }
+
+            for field in table.into_fields() {
+                self.merge_field(field, strategy, &mut on_set)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_field(
+        &mut self,
+        field: Field<'a>,
+        strategy: MergeStrategy,
+        on_set: &mut impl FnMut(&Key<'a>),
+    ) -> Result<(), Key<'a>> {
+        let (key, value) = field.to_kv();
+
+        if self.get(&key).is_some() {
+            match strategy {
+                MergeStrategy::LastWins => (),
+                MergeStrategy::FirstWins => return Ok(()),
+                MergeStrategy::Error => return Err(key),
+            }
+        }
+
+        on_set(&key);
+        self.insert(key, value);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeError, MergeStrategy};
+    use crate::{model::key::CONSTRUCTOR as key, Document, Parse, Value};
+
+    const SHARED: &str = "top = 1\n[db]\nport = 2020\nuser = \"app\"\n";
+    const LOCAL: &str = "top = 2\n[db]\nport = 5432\n[cache]\nsize = 64\n";
+
+    fn merged(strategy: MergeStrategy) -> Result<Document<'static>, MergeError> {
+        let mut document = Document::parse_str(SHARED).expect("parsing should not fail");
+        document.merge(
+            Document::parse_str(LOCAL).expect("parsing should not fail"),
+            strategy,
+        )?;
+        Ok(document)
+    }
+
+    #[test]
+    fn last_wins() {
+        let document = merged(MergeStrategy::LastWins).expect("merging should not fail");
+
+        assert_eq!(document.get(&key(&["top"])), Some(&Value::Integer(2)));
+        assert_eq!(
+            document.get(&key(&["db", "port"])),
+            Some(&Value::Integer(5432))
+        );
+        assert_eq!(
+            document.get(&key(&["db", "user"])),
+            Some(&Value::String("app"))
+        );
+        assert_eq!(
+            document
+                .table(&key(&["cache"]))
+                .and_then(|cache| cache.get(&key(&["size"]))),
+            Some(&Value::Integer(64))
+        );
+    }
+
+    #[test]
+    fn first_wins() {
+        let document = merged(MergeStrategy::FirstWins).expect("merging should not fail");
+
+        assert_eq!(document.get(&key(&["top"])), Some(&Value::Integer(1)));
+        assert_eq!(
+            document.get(&key(&["db", "port"])),
+            Some(&Value::Integer(2020))
+        );
+        assert_eq!(
+            document.get(&key(&["cache", "size"])),
+            Some(&Value::Integer(64))
+        );
+    }
+
+    #[test]
+    fn error() {
+        assert!(matches!(
+            merged(MergeStrategy::Error),
+            Err(MergeError::Conflict(conflict)) if conflict == "top"
+        ));
+    }
+}
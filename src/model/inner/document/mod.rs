@@ -1,56 +1,345 @@
-use crate::{Field, Parse, Set, Table};
+use std::{borrow::Cow, fmt};
+
+use crate::{Field, NamingStrategy, Set, Table, Value, ValueFormat};
 
 mod parse;
 pub use parse::Error as ParseError;
 
-use super::Key;
+mod merge;
+pub use merge::{MergeError, MergeStrategy};
+
+mod profile;
+pub use profile::ProfileError;
+
+mod names;
+pub use names::{NameIssue, NameProblem, Strictness, VarCollision};
+
+#[cfg(feature = "schema")]
+mod validate;
+#[cfg(feature = "schema")]
+pub use validate::Violation;
+
+use super::{key::KeyRef, value::Format, Ident, Key};
 
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct Document<'a> {
-    basename: Option<String>,
-    fields: Set<'a, Field<'a>>,
-    tables: Set<'a, Table<'a>>,
+    basename: Option<Cow<'a, str>>,
+    /// Whether `basename` was declared in the text with `@basename`, rather than set afterwards.
+    basename_declared: bool,
+    includes: Vec<&'a str>,
+    fields: Set<Field<'a>>,
+    tables: Set<Table<'a>>,
 }
 
 impl<'a> Document<'a> {
-    pub fn set_basename(&mut self, value: String) {
-        self.basename = Some(value)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Sets the name prepended to every variable, replacing any declared with `@basename`.
+
+    The basename is parsed as a [`Key`] when variables are named, and ignored if it isn't one;
+    [`LoadOptions`](crate::LoadOptions) rejects invalid basenames up front.
+    */
+    pub fn set_basename(&mut self, value: impl Into<Cow<'a, str>>) {
+        self.basename = Some(value.into());
+        self.basename_declared = false;
     }
 
-    pub fn fields(&self) -> impl Iterator<Item = &Field> {
+    pub fn basename(&self) -> Option<&str> {
+        self.basename.as_deref()
+    }
+
+    /// Whether the basename was declared in the document's text with `@basename`, rather than [set](Self::set_basename).
+    pub fn declares_basename(&self) -> bool {
+        self.basename_declared
+    }
+
+    /// Removes the basename, so variables are named after their keys alone.
+    pub fn take_basename(&mut self) -> Option<Cow<'a, str>> {
+        self.basename_declared = false;
+        self.basename.take()
+    }
+
+    /// The paths of the documents this one `@include`s, as written.
+    pub fn includes(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.includes.iter().copied()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = &Field<'a>> {
         self.fields.iter()
     }
 
-    pub fn tables(&self) -> impl Iterator<Item = &Table> {
+    pub fn tables(&self) -> impl Iterator<Item = &Table<'a>> {
         self.tables.iter()
     }
 
-    pub fn vars(self) -> impl Iterator<Item = (String, String)> {
+    pub fn table(&self, name: &Key) -> Option<&Table<'a>> {
+        self.tables.get(name)
+    }
+
+    /**
+    Returns a mutable reference to the table with the given name.
+
+    The table mustn't be replaced through this reference with one of a different name, or it can't be
+    found by either name; use [`remove_table`](Self::remove_table) and [`insert_table`](Self::insert_table) instead.
+    */
+    pub fn table_mut(&mut self, name: &Key) -> Option<&mut Table<'a>> {
+        self.tables.get_mut(name)
+    }
+
+    /// Adds a table, replacing any table with the same name.
+    pub fn insert_table(&mut self, table: Table<'a>) -> Option<Table<'a>> {
+        self.tables.replace(table)
+    }
+
+    pub fn remove_table(&mut self, name: &Key) -> Option<Table<'a>> {
+        self.tables.remove(name)
+    }
+
+    /// Finds the table that should hold `key`: the one with the longest name that `key` starts with.
+    fn table_for(&self, key: &Key) -> Option<&Key<'a>> {
+        self.tables
+            .iter()
+            .map(Table::name)
+            .filter(|name| key.relative_to(name).is_some())
+            .max_by_key(|name| name.len())
+    }
+
+    /// Looks up a value by its full key, whether it's a top-level field or part of a table.
+    pub fn get(&self, key: &[Ident]) -> Option<&Value<'a>> {
+        if let Some(field) = self.fields.get(key) {
+            return Some(field.value());
+        }
+
+        self.tables.iter().find_map(|table| {
+            let relative = key.strip_prefix(&**table.name())?;
+            table.get(relative)
+        })
+    }
+
+    /**
+    Sets a value by its full key.
+
+    If the document has a table that `key` belongs to, the value is set in that table;
+    otherwise, it's set as a top-level field. Returns the previous value, if there was one.
+
+    ```
+    # use nvee::{Document, Key, Parse, Value};
+    let mut document = Document::parse_str("[db]\nport = 2020\n").unwrap();
+
+    let port = Key::from_segments(["db", "port"]).unwrap();
+    assert_eq!(document.insert(port.clone(), Value::Integer(5432)), Some(Value::Integer(2020)));
+    assert_eq!(document.get(&port), Some(&Value::Integer(5432)));
+    ```
+    */
+    pub fn insert(&mut self, key: Key<'a>, value: Value<'a>) -> Option<Value<'a>> {
+        if self.fields.contains(&key) {
+            return self
+                .fields
+                .replace(Field::new(key, value))
+                .map(|field| field.to_kv().1);
+        }
+
+        if let Some(table) = self.tables.iter_mut().find(|table| {
+            key.relative_to(table.name())
+                .is_some_and(|relative| table.get(relative).is_some())
+        }) {
+            let relative = key.strip_prefix(table.name())?;
+            return table.insert(relative, value);
+        }
+
+        if let Some(name) = self.table_for(&key).cloned() {
+            let relative = key.strip_prefix(&name)?;
+            self.tables.get_mut(&name)?.insert(relative, value);
+        } else {
+            self.fields.insert(Field::new(key, value));
+        }
+
+        None
+    }
+
+    /// Removes a value by its full key, whether it's a top-level field or part of a table.
+    pub fn remove(&mut self, key: &[Ident]) -> Option<Value<'a>> {
+        if let Some(field) = self.fields.remove(key) {
+            return Some(field.to_kv().1);
+        }
+
+        self.tables.iter_mut().find_map(|table| {
+            let relative = key.strip_prefix(&**table.name())?;
+            table.remove(relative)
+        })
+    }
+
+    /// Iterates over every value with its full key, top-level fields first.
+    pub(crate) fn values_mut(&mut self) -> impl Iterator<Item = (KeyRef<'_, 'a>, &mut Value<'a>)> {
+        let fields = self.fields.iter_mut().map(|field| {
+            let (key, value) = field.parts_mut();
+            (KeyRef::new(&[], key), value)
+        });
+
+        fields.chain(self.tables.iter_mut().flat_map(Table::values_mut))
+    }
+
+    /// Moves every field and table of this document under `prefix`.
+    pub(crate) fn nest(self, prefix: &Key<'a>) -> Self {
         let Self {
             basename,
+            basename_declared,
+            includes,
             fields,
             tables,
         } = self;
 
-        let base = basename.as_deref().and_then(|s| Key::parse_str(s).ok());
-
-        let vec: Vec<(_, _)> = {
-            let chained = fields
+        Self {
+            basename,
+            basename_declared,
+            includes,
+            fields: fields
                 .into_iter()
-                .chain(tables.iter().flat_map(Table::fields));
+                .map(|field| {
+                    let (key, value) = field.to_kv();
+                    Field::new(prefix.chain(&key), value)
+                })
+                .collect(),
+            tables: tables.into_iter().map(|table| table.nest(prefix)).collect(),
+        }
+    }
 
-            if let Some(base) = base {
-                chained
-                    .map(move |field| (base.chain(field.key()).var_name(), field.value().var()))
-                    .collect()
-            } else {
-                chained
-                    .map(|field| (field.key().var_name(), field.value().var()))
-                    .collect()
-            }
+    pub fn vars(self) -> impl Iterator<Item = (String, String)> {
+        self.vars_with(&NamingStrategy::DEFAULT)
+    }
+
+    /// The basename as key segments, if there is one and `naming` uses it.
+    fn base(&self, naming: &NamingStrategy) -> Option<Key<'_>> {
+        self.basename
+            .as_deref()
+            .filter(|_| naming.uses_basename())
+            .and_then(Key::parse_whole)
+    }
+
+    /// Calls `f` with the key of every field, the name of its variable under `naming`, and its value.
+    fn for_each_var(
+        &self,
+        naming: &NamingStrategy,
+        mut f: impl FnMut(KeyRef<'_, 'a>, String, &Value<'a>),
+    ) {
+        let base = self.base(naming);
+        let base: &[Ident] = base.as_deref().unwrap_or_default();
+
+        for field in &self.fields {
+            let name = naming.var_name(KeyRef::new(base, field.key()).segments());
+            f(KeyRef::new(&[], field.key()), name, field.value());
+        }
+
+        for field in self.tables.iter().flat_map(Table::fields) {
+            let name = naming.var_name(base.iter().copied().chain(field.key().segments()));
+            f(field.key(), name, field.value());
+        }
+    }
+
+    /// Like [`vars`](Self::vars), but names the variables according to `naming`.
+    ///
+    /// Keys that become the same variable are all expanded; [`check_collisions`](Self::check_collisions) finds them.
+    pub fn vars_with(self, naming: &NamingStrategy) -> impl Iterator<Item = (String, String)> {
+        self.vars_with_format(naming, &ValueFormat::DEFAULT)
+    }
+
+    /// Like [`vars_with`](Self::vars_with), but writes values according to `format`.
+    pub fn vars_with_format(
+        self,
+        naming: &NamingStrategy,
+        format: &ValueFormat,
+    ) -> impl Iterator<Item = (String, String)> {
+        let mut vars = Vec::with_capacity(
+            self.fields.len() + self.tables.iter().map(Table::len).sum::<usize>(),
+        );
+
+        self.for_each_var(naming, |key, name, value| {
+            let format = key
+                .segments()
+                .last()
+                .map_or(Format::DEFAULT, |segment| format.format_for(segment));
+
+            vars.push((name, value.var_with(&format)));
+        });
+
+        vars.into_iter()
+    }
+
+    /**
+    Finds the first array with an item that, written according to `format`, contains the delimiter
+    the items are joined with, so its variable can't be split back into the items.
+
+    ```
+    # use nvee::{Document, Parse, ValueFormat};
+    let document = Document::parse_str("hosts = [\"a,b\", \"c\"]\npath = [\"a,b\"]\n").unwrap();
+    let key = document.find_ambiguous_array(&ValueFormat::DEFAULT).unwrap();
+
+    assert_eq!(key.to_string(), "hosts");
+    ```
+    */
+    pub fn find_ambiguous_array(&self, format: &ValueFormat) -> Option<Key<'a>> {
+        let is_ambiguous = |key: KeyRef<'_, 'a>, value: &Value| {
+            let (Value::Array(items), Some(segment)) = (value, key.segments().last()) else {
+                return false;
+            };
+            let format = format.format_for(segment);
+
+            items
+                .iter()
+                .any(|item| item.var_with(&format).contains(format.delimiter))
         };
 
-        vec.into_iter()
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| (KeyRef::new(&[], field.key()), field.value()));
+        let table_fields = self
+            .tables
+            .iter()
+            .flat_map(Table::fields)
+            .map(|field| (field.key(), field.value()));
+
+        fields
+            .chain(table_fields)
+            .find(|&(key, value)| is_ambiguous(key, value))
+            .map(|(key, _)| key.to_key())
+    }
+
+    /**
+    Looks up a value by the name of the variable it's set as under `naming`.
+
+    ```
+    # use nvee::{Document, NamingStrategy, Parse, Value};
+    let mut document = Document::parse_str("[db]\nport = 2020\n").unwrap();
+    document.set_basename("example");
+
+    let port = Some(&Value::Integer(2020));
+    assert_eq!(document.get_var("EXAMPLE_DB_PORT", &NamingStrategy::DEFAULT), port);
+    assert_eq!(document.get_var("DB_PORT", &NamingStrategy::new().basename(false)), port);
+    ```
+    */
+    pub fn get_var(&self, name: &str, naming: &NamingStrategy) -> Option<&Value<'a>> {
+        let base = self.base(naming);
+        let base: &[Ident] = base.as_deref().unwrap_or_default();
+
+        let field = self
+            .fields
+            .iter()
+            .find(|field| naming.var_name(KeyRef::new(base, field.key()).segments()) == name)
+            .map(Field::value);
+
+        field.or_else(|| {
+            self.tables
+                .iter()
+                .flat_map(Table::fields)
+                .find(|field| {
+                    naming.var_name(base.iter().copied().chain(field.key().segments())) == name
+                })
+                .map(|field| field.value())
+        })
     }
 
     /**
@@ -73,9 +362,19 @@ impl<'a> Document<'a> {
       first lines of the `main` function.
     */
     pub unsafe fn set_vars(self) -> Vec<String> {
+        self.set_vars_with(&NamingStrategy::DEFAULT)
+    }
+
+    /**
+    Like [`set_vars`](Document::set_vars), but names the variables according to `naming`.
+
+    # Safety
+    See [`set_vars`](Document::set_vars).
+    */
+    pub unsafe fn set_vars_with(self, naming: &NamingStrategy) -> Vec<String> {
         let mut already_set = Vec::new();
 
-        for (key, value) in self.vars() {
+        for (key, value) in self.vars_with(naming) {
             if std::env::var(&key).is_ok() {
                 already_set.push(key);
             } else {
@@ -102,6 +401,36 @@ impl<'a> Document<'a> {
     }
 }
 
+impl fmt::Display for Document<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(basename) = self.basename() {
+            writeln!(f, "@basename {}", Value::String(basename))?;
+        }
+
+        for include in self.includes() {
+            writeln!(f, "@include {}", Value::String(include))?;
+        }
+
+        for field in self.fields() {
+            writeln!(f, "{}", field)?;
+        }
+
+        for (index, table) in self.tables().enumerate() {
+            if index != 0
+                || !self.fields.is_empty()
+                || !self.includes.is_empty()
+                || self.basename.is_some()
+            {
+                writeln!(f)?;
+            }
+
+            table.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Parse;
@@ -136,15 +465,156 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn insert_remove() {
+        use crate::{model::key::CONSTRUCTOR as key, Value};
+
+        let mut document =
+            Document::parse_str(parse::tests::EXAMPLE).expect("parsing should not fail");
+
+        assert_eq!(
+            document.insert(key(&["db", "port"]), Value::Integer(5432)),
+            Some(Value::Integer(2020))
+        );
+        assert_eq!(
+            document.insert(key(&["db", "user"]), Value::String("kate")),
+            None
+        );
+        assert_eq!(document.insert(key(&["debug"]), Value::Integer(1)), None);
+
+        assert_eq!(
+            document
+                .table(&key(&["db"]))
+                .and_then(|db| db.get(&key(&["user"]))),
+            Some(&Value::String("kate"))
+        );
+        assert_eq!(document.fields().count(), 1);
+
+        assert_eq!(
+            document.remove(&key(&["db", "url"])),
+            Some(Value::String("https://example.com"))
+        );
+        assert_eq!(document.remove(&key(&["db", "url"])), None);
+
+        document
+            .table_mut(&key(&["db"]))
+            .expect("table should exist")
+            .remove(&key(&["user"]));
+
+        document.assert_vars([
+            ("DB_PORT", Some("5432")),
+            ("DB_URL", None),
+            ("DB_USER", None),
+            ("DEBUG", Some("1")),
+        ]);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let text = format!("@basename \"example\"\n{}", parse::tests::EXAMPLE);
+        let document = Document::parse_str(&text).expect("parsing should not fail");
+        let displayed = document.to_string();
+
+        pretty_assertions::assert_eq!(
+            Document::parse_str(&displayed).expect("parsing displayed document should not fail"),
+            document
+        );
+    }
+
     #[test]
     fn vars_basename() {
         let mut document =
             Document::parse_str(parse::tests::EXAMPLE).expect("parsing should not fail");
-        document.set_basename("example".to_owned());
+        document.set_basename("example");
 
         document.assert_vars([
             ("EXAMPLE_DB_URL", Some("https://example.com")),
             ("EXAMPLE_DB_PORT", Some("2020")),
         ]);
     }
+
+    #[test]
+    fn vars_naming() {
+        use crate::{Case, NamingStrategy, Value};
+
+        let mut document =
+            Document::parse_str(parse::tests::EXAMPLE).expect("parsing should not fail");
+        document.set_basename("example");
+
+        let naming = NamingStrategy::new()
+            .separator("__")
+            .case(Case::Lower)
+            .basename(false);
+
+        assert_eq!(
+            document.get_var("db__port", &naming),
+            Some(&Value::Integer(2020))
+        );
+        assert_eq!(document.get_var("EXAMPLE_DB_PORT", &naming), None);
+        assert_eq!(
+            document
+                .vars_with(&naming)
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            ["db__url", "db__port"]
+        );
+    }
+
+    #[test]
+    fn vars_arrays() {
+        let document = Document::parse_str(
+            "hosts = [\"a.example\", \"b.example\"]\n\n[python]\npath = [\n  \"/opt/lib\",\n  \"/usr/lib\",\n]\nports = [80, 443]\n",
+        )
+        .expect("parsing should not fail");
+
+        document.assert_vars([
+            ("HOSTS", Some("a.example,b.example")),
+            ("PYTHON_PATH", Some("/opt/lib:/usr/lib")),
+            ("PYTHON_PORTS", Some("80,443")),
+        ]);
+    }
+
+    #[test]
+    fn vars_datetimes() {
+        let document = Document::parse_str(
+            "expiry = 2031-01-01 00:00:00Z # renew before then\ncutoffs = [2024-06-01, 12:00:00]\n",
+        )
+        .expect("parsing should not fail");
+
+        document.assert_vars([
+            ("EXPIRY", Some("2031-01-01T00:00:00Z")),
+            ("CUTOFFS", Some("2024-06-01,12:00:00")),
+        ]);
+    }
+
+    #[test]
+    fn vars_units() {
+        use crate::{
+            model::value::{DurationUnit, SizeUnit},
+            NamingStrategy, ValueFormat,
+        };
+
+        let text = "timeout = 1m30s\ncache_size = 512MiB\n";
+        let document = Document::parse_str(text).expect("parsing should not fail");
+        let format = ValueFormat::new()
+            .duration_unit(DurationUnit::Seconds)
+            .size_unit(SizeUnit::Mebibytes);
+
+        assert_eq!(
+            document.clone().vars().collect::<Vec<_>>(),
+            [
+                ("TIMEOUT".to_owned(), "90000".to_owned()),
+                ("CACHE_SIZE".to_owned(), "536870912".to_owned()),
+            ]
+        );
+        assert_eq!(
+            document
+                .vars_with_format(&NamingStrategy::DEFAULT, &format)
+                .collect::<Vec<_>>(),
+            [
+                ("TIMEOUT".to_owned(), "90".to_owned()),
+                ("CACHE_SIZE".to_owned(), "512".to_owned()),
+            ]
+        );
+    }
 }
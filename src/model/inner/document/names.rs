@@ -0,0 +1,360 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{Key, NamingStrategy};
+
+use super::Document;
+
+/// Which variable names [`Document::check_names`] accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strictness {
+    /// Names a POSIX shell can assign: ASCII letters, digits and `_`, not starting with a digit.
+    #[default]
+    Portable,
+
+    /// Like [`Portable`](Self::Portable), but without lowercase letters,
+    /// as POSIX recommends for variables shared between programs.
+    Uppercase,
+
+    /// Any name the operating system accepts: non-empty, without `=` or NUL.
+    Lenient,
+}
+
+/// Variables that most programs and shells rely on, which a document probably shouldn't override.
+const WELL_KNOWN: &[&str] = &[
+    "HOME",
+    "HOSTNAME",
+    "IFS",
+    "LANG",
+    "LD_LIBRARY_PATH",
+    "LD_PRELOAD",
+    "LOGNAME",
+    "PATH",
+    "PS1",
+    "PWD",
+    "SHELL",
+    "TERM",
+    "TMPDIR",
+    "TZ",
+    "USER",
+];
+
+/// What's wrong with a variable name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameProblem {
+    Empty,
+    LeadingDigit,
+    IllegalChar(char),
+    Lowercase(char),
+    /// The name is that of a well-known variable, like `PATH` or `HOME`. This is only a warning.
+    Shadows(&'static str),
+}
+
+impl NameProblem {
+    /// Whether the name is still usable, just likely a mistake.
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Self::Shadows(_))
+    }
+
+    fn find(name: &str, strictness: Strictness) -> Option<Self> {
+        if name.is_empty() {
+            return Some(Self::Empty);
+        }
+
+        if strictness == Strictness::Lenient {
+            return name
+                .chars()
+                .find(|&ch| ch == '=' || ch == '\0')
+                .map(Self::IllegalChar);
+        }
+
+        if name.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return Some(Self::LeadingDigit);
+        }
+
+        name.chars().find_map(|ch| match ch {
+            'a'..='z' if strictness == Strictness::Uppercase => Some(Self::Lowercase(ch)),
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '_' => None,
+            _ => Some(Self::IllegalChar(ch)),
+        })
+    }
+}
+
+impl fmt::Display for NameProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "is empty"),
+            Self::LeadingDigit => write!(f, "starts with a digit"),
+            Self::IllegalChar(ch) => write!(f, "contains {ch:?}"),
+            Self::Lowercase(ch) => write!(f, "contains the lowercase letter {ch:?}"),
+            Self::Shadows(name) => write!(f, "overrides the well-known variable {name}"),
+        }
+    }
+}
+
+/// A problem with the name of one of a document's variables, along with the key it comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameIssue<'a> {
+    key: Key<'a>,
+    name: String,
+    problem: NameProblem,
+}
+
+impl<'a> NameIssue<'a> {
+    pub fn key(&self) -> &Key<'a> {
+        &self.key
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn problem(&self) -> NameProblem {
+        self.problem
+    }
+}
+
+impl fmt::Display for NameIssue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` becomes the variable {}, which {}",
+            self.key, self.name, self.problem
+        )
+    }
+}
+
+/// Two different keys that become the same variable, like `my-service` and `my_service` under the default naming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarCollision<'a> {
+    first: Key<'a>,
+    second: Key<'a>,
+    name: String,
+}
+
+impl<'a> VarCollision<'a> {
+    pub fn first(&self) -> &Key<'a> {
+        &self.first
+    }
+
+    pub fn second(&self) -> &Key<'a> {
+        &self.second
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for VarCollision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` and `{}` both become the variable {}",
+            self.first, self.second, self.name
+        )
+    }
+}
+
+impl<'a> Document<'a> {
+    /**
+    Checks that no two keys of this document become the same variable under `naming`.
+
+    Whether keys collide depends on the strategy: `a.b_c` and `a_b.c` are both `A_B_C` by default,
+    but `A__B_C` and `A_B__C` with `__` as the separator. Returns the first collision, in document order.
+
+    ```
+    # use nvee::{Document, NamingStrategy, Parse};
+    let document = Document::parse_str("my-service = 1\nmy_service = 2\n").unwrap();
+    let collision = document.check_collisions(&NamingStrategy::DEFAULT).unwrap_err();
+
+    assert_eq!(collision.name(), "MY_SERVICE");
+    ```
+    */
+    pub fn check_collisions(&self, naming: &NamingStrategy) -> Result<(), VarCollision<'a>> {
+        let mut names = HashMap::new();
+        let mut collision = None;
+
+        self.for_each_var(naming, |key, name, _| {
+            if collision.is_some() {
+                return;
+            }
+
+            if let Some(first) = names.insert(name.clone(), key.to_key()) {
+                collision = Some((first, key.to_key(), name));
+            }
+        });
+
+        match collision {
+            Some((first, second, name)) => Err(VarCollision {
+                first,
+                second,
+                name,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /**
+    Checks the name of every variable this document expands to under `naming`.
+
+    Names that `strictness` rejects are reported, as are names of well-known variables like `PATH`,
+    whose issues are [warnings](NameProblem::is_warning). Issues are reported in document order.
+
+    ```
+    # use nvee::{Document, NameProblem, NamingStrategy, Parse, Strictness};
+    let document = Document::parse_str("2kv = 1\npath = \"/bin\"\n").unwrap();
+    let issues = document.check_names(&NamingStrategy::DEFAULT, Strictness::Portable);
+
+    assert_eq!(issues[0].name(), "2KV");
+    assert_eq!(issues[0].problem(), NameProblem::LeadingDigit);
+    assert!(issues[1].problem().is_warning());
+    ```
+    */
+    pub fn check_names(
+        &self,
+        naming: &NamingStrategy,
+        strictness: Strictness,
+    ) -> Vec<NameIssue<'a>> {
+        let mut issues = Vec::new();
+
+        self.for_each_var(naming, |key, name, _| {
+            let problem = NameProblem::find(&name, strictness).or_else(|| {
+                WELL_KNOWN
+                    .iter()
+                    .find(|&&known| known == name)
+                    .map(|&known| NameProblem::Shadows(known))
+            });
+
+            if let Some(problem) = problem {
+                issues.push(NameIssue {
+                    key: key.to_key(),
+                    name,
+                    problem,
+                });
+            }
+        });
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NameProblem, Strictness};
+    use crate::{Case, Document, NamingStrategy, Parse};
+
+    fn problems(
+        text: &str,
+        naming: &NamingStrategy,
+        strictness: Strictness,
+    ) -> Vec<(String, String, NameProblem)> {
+        Document::parse_str(text)
+            .expect("parsing should not fail")
+            .check_names(naming, strictness)
+            .into_iter()
+            .map(|issue| {
+                (
+                    issue.key().to_string(),
+                    issue.name().to_owned(),
+                    issue.problem(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strictness() {
+        let text = "2kv = 1\nok = 3\n";
+
+        assert_eq!(
+            problems(text, &NamingStrategy::DEFAULT, Strictness::Portable),
+            [(
+                "2kv".to_owned(),
+                "2KV".to_owned(),
+                NameProblem::LeadingDigit
+            )]
+        );
+        assert_eq!(
+            problems(
+                "ok = 1",
+                &NamingStrategy::new().prefix("my.app_"),
+                Strictness::Portable
+            ),
+            [(
+                "ok".to_owned(),
+                "my.app_OK".to_owned(),
+                NameProblem::IllegalChar('.')
+            )]
+        );
+        assert!(problems(text, &NamingStrategy::DEFAULT, Strictness::Lenient).is_empty());
+
+        let lower = NamingStrategy::new().case(Case::Lower);
+        assert_eq!(
+            problems("ok = 1", &lower, Strictness::Uppercase),
+            [(
+                "ok".to_owned(),
+                "ok".to_owned(),
+                NameProblem::Lowercase('o')
+            )]
+        );
+        assert!(problems("ok = 1", &lower, Strictness::Portable).is_empty());
+    }
+
+    #[test]
+    fn collisions() {
+        for (text, first, second) in [
+            ("my-service = 1\nmy_service = 2", "my-service", "my_service"),
+            ("a.b_c = 1\n[a_b]\nc = 2", "a.b_c", "a_b.c"),
+        ] {
+            let document = Document::parse_str(text).expect("parsing should not fail");
+            let collision = document
+                .check_collisions(&NamingStrategy::DEFAULT)
+                .expect_err("keys should collide");
+
+            assert_eq!(
+                (
+                    collision.first().to_string(),
+                    collision.second().to_string()
+                ),
+                (first.to_owned(), second.to_owned())
+            );
+        }
+
+        let document =
+            Document::parse_str("a.b_c = 1\n[a_b]\nc = 2").expect("parsing should not fail");
+        assert_eq!(
+            document.check_collisions(&NamingStrategy::new().separator("__")),
+            Ok(())
+        );
+        assert!(Document::parse_str("apiKey = 1\napikey = 2")
+            .expect("parsing should not fail")
+            .check_collisions(&NamingStrategy::new().case(Case::Preserve))
+            .is_ok());
+
+        let mut document = Document::parse_str(
+            "a-b = 1
+a_b = 2",
+        )
+        .expect("parsing should not fail");
+        document.set_basename("app");
+        let collision = document
+            .check_collisions(&NamingStrategy::DEFAULT)
+            .expect_err("keys should collide");
+        assert_eq!(collision.name(), "APP_A_B");
+    }
+
+    #[test]
+    fn shadows() {
+        let mut document =
+            Document::parse_str("home = \"/root\"\n").expect("parsing should not fail");
+
+        let issues = document.check_names(&NamingStrategy::DEFAULT, Strictness::Lenient);
+        assert_eq!(issues[0].problem(), NameProblem::Shadows("HOME"));
+        assert!(issues[0].problem().is_warning());
+
+        document.set_basename("app");
+        assert!(document
+            .check_names(&NamingStrategy::DEFAULT, Strictness::Lenient)
+            .is_empty());
+    }
+}
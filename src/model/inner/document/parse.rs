@@ -1,13 +1,21 @@
+use std::{collections::HashSet, fmt};
+
 use crate::{
-    model::{field, table},
-    Parse, Set,
+    model::{
+        field::{self, Entry},
+        table,
+    },
+    parser::{Lexeme, Token},
+    Ident, Key, Parse, Set, Table, Value,
 };
 
 use super::Document;
 
 #[derive(Debug)]
 pub enum Error {
+    Directive,
     DuplicateKey(String),
+    MissingDelimiter,
     Field(field::ParseError),
     Table(table::ParseError),
 }
@@ -24,61 +32,305 @@ impl From<table::ParseError> for Error {
     }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Directive => write!(f, "expected `@basename \"name\"` or `@include \"path\"`"),
+            Self::DuplicateKey(key) => write!(f, "`{key}` is defined more than once"),
+            Self::MissingDelimiter => write!(f, "expected a new line"),
+            Self::Field(err) => err.fmt(f),
+            Self::Table(err) => err.fmt(f),
+        }
+    }
+}
+
 impl crate::Sealed for Document<'_> {}
 impl<'p> Parse<'p> for Document<'p> {
     type Err = Error;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
+        let mut basename = None;
+        let mut includes = Vec::new();
         let mut fields = Set::new();
         let mut tables = Set::new();
 
-        while input.peek_char() != Some('[') {
-            fields.insert(input.parse()?);
+        loop {
+            input.skip_trivia();
 
-            while input.parse_char_with(|ch| matches!(ch, '\n')).is_some() {
-                // consume newlines
+            match input.peek_token() {
+                Token::LeftBracket | Token::Eof => break,
+                Token::At => match input.parse::<Directive>()? {
+                    Directive::Basename(_) if basename.is_some() => return Err(Error::Directive),
+                    Directive::Basename(name) => basename = Some(name.into()),
+                    Directive::Include(path) => includes.push(path),
+                },
+                _ => {
+                    for field in input.parse::<Entry>()?.0 {
+                        if let Err(field) = fields.try_insert(field) {
+                            return Err(Error::DuplicateKey(field.key().to_string()));
+                        }
+                    }
+                }
             }
-        }
 
-        while input.peek_char().is_some() {
-            tables.insert(input.parse()?);
+            if !input.at_delimiter() {
+                return Err(Error::MissingDelimiter);
+            }
+        }
 
-            while input.parse_char_with(|ch| matches!(ch, '\n')).is_some() {
-                // mmm tasty newlines
+        while input.peek_token() != Token::Eof {
+            if let Err(table) = tables.try_insert(input.parse::<Table>()?) {
+                return Err(Error::DuplicateKey(table.name().to_string()));
             }
         }
 
-        Ok(Self {
-            basename: None,
+        let document = Self {
+            basename_declared: basename.is_some(),
+            basename,
+            includes,
             fields,
             tables,
-        })
+        };
+
+        document.check_duplicates()?;
+
+        Ok(document)
+    }
+}
+
+impl Document<'_> {
+    /// Fails on a table field with the same key as a top-level field or another table's field,
+    /// like `url` in `[db]` and a top-level `db.url`.
+    fn check_duplicates(&self) -> Result<(), Error> {
+        if self.tables.is_empty() {
+            return Ok(());
+        }
+
+        let mut keys: HashSet<Vec<Ident>> =
+            self.fields().map(|field| field.key().to_vec()).collect();
+
+        for key in self
+            .tables()
+            .flat_map(Table::fields)
+            .map(|field| field.key())
+        {
+            if !keys.insert(key.segments().collect()) {
+                return Err(Error::DuplicateKey(key.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A line starting with `@`.
+enum Directive<'p> {
+    /// `@basename "name"`, which names the document's variables regardless of its file name.
+    Basename(&'p str),
+    /// `@include "path"`.
+    Include(&'p str),
+}
+
+impl crate::Sealed for Directive<'_> {}
+impl<'p> Parse<'p> for Directive<'p> {
+    type Err = Error;
+
+    fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
+        if !input.parse_token(Token::At) {
+            return Err(Error::Directive);
+        }
+
+        let name = match input.next_lexeme() {
+            Lexeme {
+                token: Token::Bare(name),
+                after_space: false,
+                ..
+            } => name,
+            _ => return Err(Error::Directive),
+        };
+
+        if !input.peek().after_space {
+            return Err(Error::Directive);
+        }
+
+        let argument = match input.parse::<Value>() {
+            Ok(Value::String(argument)) if !argument.is_empty() => argument,
+            _ => return Err(Error::Directive),
+        };
+
+        match name {
+            "basename" if Key::parse_whole(argument).is_some() => Ok(Self::Basename(argument)),
+            "include" => Ok(Self::Include(argument)),
+            _ => Err(Error::Directive),
+        }
     }
 }
 
 #[cfg(test)]
 pub(super) mod tests {
-    use crate::{Document, Set, Table};
+    use crate::{Document, Parse, Set, Table};
 
     pub const EXAMPLE: &str = include_str!("example.nvee");
 
     macro_rules! table {
         ([$($name:literal).+] $($($key:literal).+ = $value:expr)+) => {
             {
-            let mut fields = crate::Set::new();
+            let mut table = Table::new(crate::model::key::CONSTRUCTOR([$($name),+].as_slice()));
 
             $(
-                fields.insert(crate::model::field::CONSTRUCTOR(([$($key),+].as_slice(), $value)));
+                table.insert(crate::model::key::CONSTRUCTOR([$($key),+].as_slice()), $value);
             )+
 
-            Table::new(
-                crate::model::key::CONSTRUCTOR([$($name),+].as_slice()),
-                fields,
-            )
+            table
             }
         };
     }
 
+    #[test]
+    fn includes() {
+        let document = crate::test_utils::parse_str::<Document>(
+            "@include \"db.nvee\"\n@include \"../shared/cache.nvee\"\nword = \"jaunt\"\n[table]\nlucky = 7",
+        );
+
+        assert_eq!(
+            document.includes().collect::<Vec<_>>(),
+            ["db.nvee", "../shared/cache.nvee"]
+        );
+        assert_eq!(document.fields().count(), 1);
+    }
+
+    #[test]
+    fn invalid_includes() {
+        crate::test_invalid::<Document>([
+            "@include db.nvee\n[table]\nlucky = 7",
+            "@inclde \"db.nvee\"\n[table]\nlucky = 7",
+            "@include \"\"\n[table]\nlucky = 7",
+        ]);
+    }
+
+    #[test]
+    fn basename() {
+        let mut document =
+            crate::test_utils::parse_str::<Document>("@basename \"my-app\"\nword = \"jaunt\"");
+
+        assert_eq!(document.basename(), Some("my-app"));
+        assert!(document.declares_basename());
+        assert_eq!(
+            document.clone().vars().collect::<Vec<_>>(),
+            [("MY_APP_WORD".to_owned(), "jaunt".to_owned())]
+        );
+
+        document.set_basename("my-app");
+        assert!(!document.declares_basename());
+
+        crate::test_invalid::<Document>([
+            "@basename \"\"",
+            "@basename \"my app\"",
+            "@basename app",
+            "@basename \"a\"\n@basename \"b\"",
+        ]);
+    }
+
+    #[test]
+    fn shapes() {
+        use crate::{model::key::CONSTRUCTOR as key, Value};
+
+        for empty in ["", "\n\n", "# nothing here\n", "  \r\n"] {
+            assert_eq!(
+                crate::test_utils::parse_str::<Document>(empty),
+                Document::default()
+            );
+        }
+
+        let fields_only = crate::test_utils::parse_str::<Document>(
+            "# fields only\nauthor = \"kate\" # inline comment\n\nlucky = 7",
+        );
+        assert_eq!(
+            fields_only.get(&key(&["author"])),
+            Some(&Value::String("kate"))
+        );
+        assert_eq!(fields_only.get(&key(&["lucky"])), Some(&Value::Integer(7)));
+        assert_eq!(fields_only.tables().count(), 0);
+
+        let tables_only =
+            crate::test_utils::parse_str::<Document>("[a]\nx = 1\n\n\n[b]\n\ny = 2\n\n");
+        assert_eq!(tables_only.fields().count(), 0);
+        assert_eq!(tables_only.get(&key(&["a", "x"])), Some(&Value::Integer(1)));
+        assert_eq!(tables_only.get(&key(&["b", "y"])), Some(&Value::Integer(2)));
+
+        let empty_table = crate::test_utils::parse_str::<Document>("[a]");
+        assert!(empty_table
+            .table(&key(&["a"]))
+            .is_some_and(|a| a.is_empty()));
+    }
+
+    #[test]
+    fn invalid() {
+        crate::test_invalid::<Document>([
+            "x = 1\nx = 2",
+            "[a]\nx = 1\n[a]\ny = 2",
+            "x = 1\n[a]\ny = \"unclosed\n",
+            "x = 1\ny\n",
+            "db.url = 1\n[db]\nurl = 2",
+            "[a]\nb.c = 1\n[a.b]\nc = 2",
+            "x = 1 y = 2",
+            "@include \"a.nvee\" x = 1",
+        ]);
+    }
+
+    #[test]
+    fn located() {
+        for (text, line, column, message) in [
+            ("x = 1\ny = \"unclosed\n", 2, 5, "unclosed string"),
+            ("x = 1\n\n[a]\n  y = \"a\"z", 4, 10, "expected a new line"),
+            ("\"a b\" = 1", 1, 1, "a key can't contain ' '"),
+        ] {
+            let err = Document::parse_located(text).expect_err("document should be invalid");
+
+            assert_eq!((err.line, err.column), (line, column), "{text:?}");
+            assert_eq!(err.error.to_string(), message, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn inline_tables() {
+        use crate::{model::key::CONSTRUCTOR as key, Value};
+
+        let document = crate::test_utils::parse_str::<Document>(
+            "db = { url = \"x\", port = 5432, pool = { size = 4 } }\nempty = {}\n[cache]\nredis = {host = \"localhost\"}",
+        );
+
+        assert_eq!(
+            document.get(&key(&["db", "url"])),
+            Some(&Value::String("x"))
+        );
+        assert_eq!(
+            document.get(&key(&["db", "pool", "size"])),
+            Some(&Value::Integer(4))
+        );
+        assert_eq!(
+            document.get(&key(&["cache", "redis", "host"])),
+            Some(&Value::String("localhost"))
+        );
+        assert_eq!(document.fields().count(), 3);
+
+        crate::test_invalid::<Document>([
+            "db = { url = \"x\" port = 1 }",
+            "db = { url = \"x\",\nport = 1 }",
+            "db = { url = \"x\"",
+            "db = { url = \"x\", url = \"y\" }",
+            "db = { url = \"x\" }\ndb.url = \"y\"",
+            "db = { url = \"x\" }\n[db]\nurl = \"y\"",
+            "db.url = \"x\"\n[db]\nurl = \"y\"",
+            "[a]\nb = { c = 1 }\nb.c = 2",
+        ]);
+
+        assert!(matches!(
+            Document::parse_str("db = { url = \"x\" }\n[db]\nurl = \"y\""),
+            Err(super::Error::DuplicateKey(duplicate)) if duplicate == "db.url"
+        ));
+    }
+
     #[test]
     fn example() {
         use crate::Value;
@@ -95,6 +347,7 @@ pub(super) mod tests {
             crate::test_utils::parse_str::<Document>(EXAMPLE),
             Document {
                 basename: None,
+                includes: Vec::new(),
                 tables,
                 ..Default::default()
             }
@@ -0,0 +1,257 @@
+use std::fmt;
+
+use crate::{model::key::KeyRef, Key, MergeStrategy, Table};
+
+use super::Document;
+
+/// Why [`Document::select_profile`] failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError<'a> {
+    /// A field set directly under `profile`, like `profile = 1` or `x` in `[profile]`, rather than in a profile section.
+    Misplaced(Key<'a>),
+    /// A selected profile that no section defines, including names that can't be key segments, like `bad name`.
+    Undefined(String),
+}
+
+impl fmt::Display for ProfileError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Misplaced(key) => write!(
+                f,
+                "`{key}` is set directly under `profile`, which only holds profile sections like `[profile.dev]`"
+            ),
+            Self::Undefined(name) => write!(f, "no section defines the profile `{name}`"),
+        }
+    }
+}
+
+impl<'a> Document<'a> {
+    /// The first key segment of profile sections, such as `[profile.dev.db]`.
+    pub const PROFILE: &'static str = "profile";
+
+    /**
+    Applies the overrides of the profile called `name`, then removes every profile section.
+
+    A table like `[profile.dev.db]` overrides the fields of `[db]` when the `dev` profile is selected,
+    and `[profile.dev]` overrides top-level fields. Fields like `profile.dev.db.port = 1` work the same way.
+    With no profile selected, profile sections are only removed.
+
+    `profile` is reserved for profile sections, so this fails with [`ProfileError::Misplaced`] on a field set directly under it,
+    like `profile = 1` or `x` in `[profile]`, which would otherwise be silently dropped. Selecting a profile that no
+    table or field defines fails with [`ProfileError::Undefined`], so a misspelled name doesn't quietly load the base values.
+
+    ```
+    # use nvee::{Document, Key, Parse, Value};
+    let mut document = Document::parse_str(
+        "[db]\nport = 2020\n\n[profile.prod.db]\nport = 5432\n"
+    ).unwrap();
+
+    document.select_profile(Some("prod")).unwrap();
+
+    let port = Key::from_segments(["db", "port"]).unwrap();
+    assert_eq!(document.get(&port), Some(&Value::Integer(5432)));
+    assert_eq!(document.tables().count(), 1);
+    ```
+    */
+    pub fn select_profile(&mut self, name: Option<&str>) -> Result<(), ProfileError<'a>> {
+        let root = Key::from_segments([Self::PROFILE]).expect("profile root should be a valid key");
+
+        let misplaced = self
+            .fields()
+            .map(|field| KeyRef::new(&[], field.key()))
+            .chain(
+                self.tables()
+                    .flat_map(Table::fields)
+                    .map(|field| field.key()),
+            )
+            .find(|key| key.segments().next() == Some(root[0]) && key.segments().count() <= 2);
+
+        if let Some(key) = misplaced {
+            return Err(ProfileError::Misplaced(key.to_key()));
+        }
+
+        let selected = match name {
+            Some(name) => Some(
+                Key::from_segments([Self::PROFILE, name])
+                    .map_err(|_| ProfileError::Undefined(name.to_owned()))?,
+            ),
+            None => None,
+        };
+        let mut defined = false;
+
+        let mut overrides = Document::new();
+
+        let profile_tables: Vec<_> = self
+            .tables()
+            .map(Table::name)
+            .filter(|name| name.starts_with(&root))
+            .cloned()
+            .collect();
+
+        for name in profile_tables {
+            let table = self.remove_table(&name).expect("table should exist");
+
+            let Some(selected) = &selected else {
+                continue;
+            };
+
+            defined |= name.starts_with(selected);
+
+            if name == *selected {
+                for field in table.into_fields() {
+                    let (key, value) = field.to_kv();
+                    overrides.insert(
+                        key.strip_prefix(selected)
+                            .expect("field should be in profile"),
+                        value,
+                    );
+                }
+            } else if let Some(stripped) = name.strip_prefix(selected) {
+                let mut stripped = Table::new(stripped);
+
+                for field in table.into_fields() {
+                    let (key, value) = field.to_kv();
+                    stripped.insert(
+                        key.strip_prefix(&name).expect("field should be in table"),
+                        value,
+                    );
+                }
+
+                overrides.insert_table(stripped);
+            }
+        }
+
+        let profile_fields: Vec<_> = self
+            .fields()
+            .map(|field| field.key())
+            .filter(|key| key.starts_with(&root))
+            .cloned()
+            .collect();
+
+        for key in profile_fields {
+            let value = self.remove(&key).expect("field should exist");
+
+            if let Some(stripped) = selected
+                .as_ref()
+                .and_then(|selected| key.strip_prefix(selected))
+            {
+                defined = true;
+                overrides.insert(stripped, value);
+            }
+        }
+
+        if let (Some(name), false) = (name, defined) {
+            return Err(ProfileError::Undefined(name.to_owned()));
+        }
+
+        self.merge_with(overrides, MergeStrategy::LastWins, |_| ())
+            .expect("last-wins merges should not conflict");
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProfileError;
+    use crate::{model::key::CONSTRUCTOR as key, Document, Parse, Value};
+
+    const EXAMPLE: &str = "\
+debug = 0
+profile.dev.debug = 1
+
+[db]
+url = \"https://example.com\"
+port = 2020
+
+[profile.dev]
+verbose = 1
+
+[profile.dev.db]
+url = \"http://localhost\"
+
+[profile.prod.db]
+port = 5432
+";
+
+    fn document(profile: Option<&str>) -> Document<'static> {
+        let mut document = Document::parse_str(EXAMPLE).expect("parsing should not fail");
+        document
+            .select_profile(profile)
+            .expect("profile sections should be valid");
+        document
+    }
+
+    #[test]
+    fn dev() {
+        let document = document(Some("dev"));
+
+        assert_eq!(document.get(&key(&["debug"])), Some(&Value::Integer(1)));
+        assert_eq!(document.get(&key(&["verbose"])), Some(&Value::Integer(1)));
+        assert_eq!(
+            document.get(&key(&["db", "url"])),
+            Some(&Value::String("http://localhost"))
+        );
+        assert_eq!(
+            document.get(&key(&["db", "port"])),
+            Some(&Value::Integer(2020))
+        );
+    }
+
+    #[test]
+    fn prod() {
+        let document = document(Some("prod"));
+
+        assert_eq!(document.get(&key(&["debug"])), Some(&Value::Integer(0)));
+        assert_eq!(document.get(&key(&["verbose"])), None);
+        assert_eq!(
+            document.get(&key(&["db", "port"])),
+            Some(&Value::Integer(5432))
+        );
+    }
+
+    #[test]
+    fn none() {
+        let document = document(None);
+
+        assert_eq!(
+            document.get(&key(&["db", "port"])),
+            Some(&Value::Integer(2020))
+        );
+        assert_eq!(document.fields().count(), 1);
+        assert_eq!(document.tables().count(), 1);
+    }
+
+    #[test]
+    fn misplaced() {
+        for (text, misplaced) in [
+            ("profile = 1", "profile"),
+            ("profile.dev = 1", "profile.dev"),
+            ("[profile]\ndev = 1", "profile.dev"),
+            ("[profile.dev]\nx = 1\n[profile]\nprod = 1", "profile.prod"),
+        ] {
+            let mut document = Document::parse_str(text).expect("parsing should not fail");
+            let err = document
+                .select_profile(None)
+                .expect_err("the field should be misplaced");
+
+            assert!(
+                matches!(&err, ProfileError::Misplaced(found) if found.to_string() == misplaced),
+                "{text:?}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn undefined() {
+        for name in ["prdo", "bad name", "db"] {
+            let mut document = Document::parse_str(EXAMPLE).expect("parsing should not fail");
+
+            assert_eq!(
+                document.select_profile(Some(name)),
+                Err(ProfileError::Undefined(name.to_owned())),
+                "{name:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+
+use crate::{
+    schema::{Problem, Schema},
+    Ident, Table, Value,
+};
+
+use super::Document;
+
+/// A problem with one of a document's values, or a required key it doesn't set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation<'a> {
+    key: String,
+    /// The last segment of the value's key, as stored in the document, for finding it in its source.
+    segment: Option<Ident<'a>>,
+    problem: Problem,
+}
+
+impl Violation<'_> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    /**
+    The line and column, both starting at 1, of the value's key in `text`, if the document was parsed from it.
+
+    Missing keys, and keys of values that weren't parsed, like defaults, have no location.
+    [`Sources::locate`](crate::load::Sources::locate) finds the file a violation is in.
+    */
+    pub fn locate(&self, text: &str) -> Option<(usize, usize)> {
+        self.segment?.locate(text)
+    }
+}
+
+impl fmt::Display for Violation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.key, self.problem)
+    }
+}
+
+impl<'a> Document<'a> {
+    /**
+    Checks this document against every rule of `schema`, reporting every problem, in the order of the rules.
+
+    Keys the schema doesn't mention can have any value.
+    */
+    pub fn validate(&self, schema: &Schema) -> Result<(), Vec<Violation<'a>>> {
+        let mut violations = Vec::new();
+
+        for rule in schema.rules() {
+            let key = rule.key();
+            let found = self.find(&key);
+
+            for problem in rule.problems(found.map(|(_, value)| value)) {
+                violations.push(Violation {
+                    key: key.to_string(),
+                    segment: found.map(|(segment, _)| segment),
+                    problem,
+                });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Sets the default value of every rule of `schema` whose key this document doesn't set.
+    pub fn fill_defaults(&mut self, schema: &'a Schema) {
+        for rule in schema.rules() {
+            let key = rule.key();
+
+            if self.get(&key).is_none() {
+                if let Some(default) = rule.default() {
+                    self.insert(key, default);
+                }
+            }
+        }
+    }
+
+    /// Like [`get`](Self::get), but also returns the last segment of the value's key as stored.
+    fn find(&self, key: &[Ident]) -> Option<(Ident<'a>, &Value<'a>)> {
+        if let Some(field) = self.fields.get(key) {
+            return Some((*field.key().last_segment(), field.value()));
+        }
+
+        self.tables
+            .iter()
+            .flat_map(Table::fields)
+            .find(|field| field.key().segments().eq(key.iter().copied()))
+            .and_then(|field| Some((field.key().segments().last()?, field.value())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schema::{Problem, Schema, Type},
+        Document, Parse, Value,
+    };
+
+    const SCHEMA: &str = "\
+[db.port]
+type = \"integer\"
+max = 65535
+default = 5432
+
+[db.url]
+required = \"true\"
+
+[\"log-level\"]
+enum = [\"debug\", \"info\"]
+default = \"info\"
+";
+
+    fn schema() -> Schema {
+        Schema::parse(SCHEMA).expect("schema should be valid")
+    }
+
+    #[test]
+    fn validate() {
+        let text = "\"log-level\" = \"trace\"\n\n[db]\nport = 70000\n";
+        let document = Document::parse_str(text).expect("parsing should not fail");
+
+        let violations = document
+            .validate(&schema())
+            .expect_err("document should be invalid");
+        let found: Vec<_> = violations
+            .iter()
+            .map(|violation| (violation.to_string(), violation.locate(text)))
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                ("`db.port` must be at most 65535".to_owned(), Some((4, 1))),
+                ("`db.url` is required".to_owned(), None),
+                (
+                    "`log-level` must be one of \"debug\", \"info\"".to_owned(),
+                    Some((1, 1))
+                ),
+            ]
+        );
+        assert_eq!(violations[0].locate("port = 70000\n"), None);
+
+        let valid = Document::parse_str("[db]\nurl = \"postgres://\"\nport = 2020\n")
+            .expect("parsing should not fail");
+        assert_eq!(valid.validate(&schema()), Ok(()));
+
+        let string = Document::parse_str("[db]\nurl = \"x\"\nport = \"2020\"\n")
+            .expect("parsing should not fail");
+        assert_eq!(
+            string
+                .validate(&schema())
+                .map_err(|violations| violations[0].problem().clone()),
+            Err(Problem::Type {
+                expected: Type::Integer,
+                found: Type::String
+            })
+        );
+    }
+
+    #[test]
+    fn fill_defaults() {
+        let schema = schema();
+        let mut document =
+            Document::parse_str("[db]\nport = 2020\n").expect("parsing should not fail");
+
+        document.fill_defaults(&schema);
+
+        assert_eq!(
+            document.get(&crate::model::key::CONSTRUCTOR(&["db", "port"])),
+            Some(&Value::Integer(2020))
+        );
+        assert_eq!(
+            document.vars().collect::<Vec<_>>(),
+            [
+                ("LOG_LEVEL".to_owned(), "info".to_owned()),
+                ("DB_PORT".to_owned(), "2020".to_owned()),
+            ]
+        );
+    }
+}
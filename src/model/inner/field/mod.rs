@@ -1,6 +1,7 @@
 use std::fmt;
 
 mod parse;
+pub(crate) use parse::Entry;
 pub use parse::Error as ParseError;
 
 #[cfg(test)]
@@ -13,22 +14,31 @@ pub struct Field<'kv> {
 }
 
 impl<'kv> Field<'kv> {
+    pub fn new(key: crate::Key<'kv>, value: crate::Value<'kv>) -> Self {
+        Self { key, value }
+    }
+
     pub fn key(&self) -> &crate::Key<'kv> {
         &self.key
     }
 
-    pub fn value(&self) -> crate::Value<'kv> {
-        self.value
+    pub fn value(&self) -> &crate::Value<'kv> {
+        &self.value
+    }
+
+    /// The key, along with mutable access to the value, which can't change the field's place in a set.
+    pub(crate) fn parts_mut(&mut self) -> (&crate::Key<'kv>, &mut crate::Value<'kv>) {
+        (&self.key, &mut self.value)
     }
 
     pub fn to_kv(self) -> (crate::Key<'kv>, crate::Value<'kv>) {
         (self.key, self.value)
     }
 
-    pub fn with_parent(&'kv self, key: &'kv crate::Key<'kv>) -> Self {
+    pub fn with_parent(&self, key: &crate::Key<'kv>) -> Self {
         Self {
             key: key.chain(&self.key),
-            value: self.value,
+            value: self.value.clone(),
         }
     }
 }
@@ -40,7 +50,7 @@ impl fmt::Display for Field<'_> {
 }
 
 impl crate::set::KeyEq for Field<'_> {
-    fn key(&self) -> &super::Key {
+    fn key(&self) -> &super::Key<'_> {
         self.key()
     }
 }
@@ -1,6 +1,9 @@
+use std::fmt;
+
 use crate::{
     model::{key, value},
-    Parse,
+    parser::Token,
+    Key, Parse,
 };
 
 use super::Field;
@@ -10,6 +13,8 @@ pub enum Error {
     Key(key::ParseError),
     Separator,
     Value(value::ParseError),
+    /// An inline table is missing a `,` between fields or its closing `}`.
+    UnclosedInlineTable,
 }
 
 impl From<key::ParseError> for Error {
@@ -24,6 +29,17 @@ impl From<value::ParseError> for Error {
     }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(err) => err.fmt(f),
+            Self::Separator => write!(f, "expected ` = ` after the key"),
+            Self::Value(err) => err.fmt(f),
+            Self::UnclosedInlineTable => write!(f, "expected `,` or `}}` in inline table"),
+        }
+    }
+}
+
 impl crate::Sealed for Field<'_> {}
 impl<'p: 'kv, 'kv> Parse<'p> for Field<'kv> {
     type Err = Error;
@@ -37,6 +53,61 @@ impl<'p: 'kv, 'kv> Parse<'p> for Field<'kv> {
     }
 }
 
+/**
+A `key = value` line, where the value may be an inline table like `{ url = "x", port = 5432 }`.
+
+Inline tables are flattened into one field per value, with the table's key prepended,
+the same fields a `[key]` section would hold.
+*/
+pub(crate) struct Entry<'p>(pub(crate) Vec<Field<'p>>);
+
+impl crate::Sealed for Entry<'_> {}
+impl<'p> Parse<'p> for Entry<'p> {
+    type Err = Error;
+
+    fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
+        let mut fields = Vec::new();
+        let key = input.parse()?;
+        input.parse::<Separator>()?;
+
+        Self::parse_value(input, key, &mut fields)?;
+
+        Ok(Self(fields))
+    }
+}
+
+impl<'p> Entry<'p> {
+    fn parse_value(
+        input: &mut crate::Parser<'p>,
+        key: Key<'p>,
+        fields: &mut Vec<Field<'p>>,
+    ) -> Result<(), Error> {
+        if !input.parse_token(Token::LeftBrace) {
+            fields.push(Field::new(key, input.parse()?));
+            return Ok(());
+        }
+
+        if input.parse_token(Token::RightBrace) {
+            return Ok(());
+        }
+
+        loop {
+            let child = input.parse::<Key>()?;
+            input.parse::<Separator>()?;
+
+            Self::parse_value(input, key.chain(&child), fields)?;
+
+            if input.parse_token(Token::RightBrace) {
+                return Ok(());
+            }
+
+            if !input.parse_token(Token::Comma) {
+                return Err(Error::UnclosedInlineTable);
+            }
+        }
+    }
+}
+
 struct Separator;
 
 impl crate::Sealed for Separator {}
@@ -44,8 +115,10 @@ impl<'p> Parse<'p> for Separator {
     type Err = Error;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
-        (input.parse_char(' ') && input.parse_char('=') && input.parse_char(' '))
-            .then_some(Self)
+        input
+            .next_if(|next| next.token == Token::Equals && next.after_space)
+            .filter(|_| input.peek().after_space)
+            .map(|_| Self)
             .ok_or(Error::Separator)
     }
 }
@@ -1,9 +1,19 @@
-use std::ops::Deref;
+use std::{fmt, ops::Deref};
 
-use crate::Parse;
+use crate::{parser::Token, Parse};
 
-pub const CHAR_LEGAL: fn(char) -> bool = |ch| matches!(ch, 'a'..='z' | '0'..='9' | '_');
+/// Characters allowed in identifiers, quoted or not: those that normalize to part of a portable variable name.
+pub const CHAR_LEGAL: fn(char) -> bool =
+    |ch| matches!(ch, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-');
 
+/**
+A single segment of a [`Key`](crate::Key), like `db` in `db.port`.
+
+Identifiers are written bare, like `api_key`, `apiKey` or `my-service`, or quoted like TOML keys, as in `"my-service"`.
+Either way, they may only contain [legal characters](CHAR_LEGAL), so `"a.b"` or `"café"` are rejected
+rather than becoming variables no shell can assign. By default, they are [normalized](Ident::var_segment) to environment variable names by uppercasing them and
+replacing hyphens with underscores; see [`NamingStrategy`](crate::NamingStrategy).
+*/
 #[derive(Hash, Debug, Clone, PartialEq, Eq, Copy)]
 pub struct Ident<'s>(&'s str);
 
@@ -15,18 +25,86 @@ impl Deref for Ident<'_> {
     }
 }
 
+impl<'s> Ident<'s> {
+    /// Creates an identifier, checking that it is non-empty and only contains [legal characters](CHAR_LEGAL).
+    pub fn new(s: &'s str) -> Result<Self, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        match s.chars().find(|&ch| !CHAR_LEGAL(ch)) {
+            Some(ch) => Err(ParseError::IllegalChar(ch)),
+            None => Ok(Self(s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'s str {
+        self.0
+    }
+
+    /// Normalizes this identifier to part of an environment variable name: `my-service` becomes `MY_SERVICE`.
+    pub fn var_segment(&self) -> String {
+        crate::NamingStrategy::DEFAULT.var_name([*self])
+    }
+
+    /**
+    The line and column, both starting at 1, where this identifier was written in `text`, if it was parsed from it.
+
+    Identifiers that point elsewhere, like ones built from a file name, have no location.
+    */
+    pub fn locate(&self, text: &str) -> Option<(usize, usize)> {
+        let offset = (self.0.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+
+        if offset + self.0.len() > text.len() {
+            return None;
+        }
+
+        // Quoted identifiers are stored without their quotes.
+        let offset = match text[..offset].ends_with('"') {
+            true => offset - 1,
+            false => offset,
+        };
+
+        Some(crate::parser::line_column(text, offset))
+    }
+}
+
+impl fmt::Display for Ident<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
 #[derive(Debug)]
-pub struct ParseError;
+pub enum ParseError {
+    Missing,
+    Empty,
+    IllegalChar(char),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "expected a key"),
+            Self::Empty => write!(f, "a key can't be empty"),
+            Self::IllegalChar(ch) => write!(f, "a key can't contain {ch:?}"),
+        }
+    }
+}
 
 impl crate::Sealed for Ident<'_> {}
 impl<'p> Parse<'p> for Ident<'p> {
     type Err = ParseError;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
-        input
-            .parse_while(|ch| matches!(ch, 'a'..='z' | '0'..='9' | '_'))
-            .map(Self)
-            .ok_or(ParseError)
+        let ident = match input.peek_token() {
+            Token::Bare(s) | Token::String(s) => Self::new(s)?,
+            _ => return Err(ParseError::Missing),
+        };
+
+        input.next_lexeme();
+
+        Ok(ident)
     }
 }
 
@@ -44,9 +122,43 @@ mod tests {
         }
 
         test_valid(["amber", "beep_boop", "kv2", "2kv", "55555"]);
+        test_valid(["API_KEY", "apiKey", "my-service"]);
+
+        crate::test_valid(
+            CONSTRUCTOR,
+            [("\"my-service\"", "my-service"), ("\"API_KEY\"", "API_KEY")],
+        );
     }
 
     test_invalid! {
-        super::Ident: "", ".", " ", "...", "???"
+        super::Ident: "", ".", " ", "...", "???", "\"\"", "\"a b\"", "\"a=b\"", "\"a.b\"", "\"café\""
+    }
+
+    #[test]
+    fn var_segment() {
+        for (ident, segment) in [
+            ("api_key", "API_KEY"),
+            ("apiKey", "APIKEY"),
+            ("my-service", "MY_SERVICE"),
+        ] {
+            assert_eq!(CONSTRUCTOR(ident).var_segment(), segment);
+        }
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(CONSTRUCTOR("my-service").to_string(), "my-service");
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            super::Ident::new("beep_boop").ok(),
+            Some(CONSTRUCTOR("beep_boop"))
+        );
+
+        for s in ["", "beep boop", "beep.boop", "béep"] {
+            assert!(super::Ident::new(s).is_err(), "'{s}' should be rejected");
+        }
     }
 }
@@ -1,6 +1,6 @@
-use std::fmt;
+use std::{borrow::Borrow, fmt, ops::Deref};
 
-use crate::Ident;
+use crate::{Ident, NamingStrategy};
 
 mod parse;
 pub use parse::Error as ParseError;
@@ -16,6 +16,30 @@ pub struct Key<'id> {
 impl<'a> Key<'a> {
     pub const SEPARATOR: char = '.';
 
+    /// Builds a key from its segments, validating each one as an [`Ident`].
+    ///
+    /// ```
+    /// # use nvee::Key;
+    /// let key = Key::from_segments(["db", "port"]).unwrap();
+    /// assert_eq!(key.to_string(), "db.port");
+    ///
+    /// assert!(Key::from_segments(["db", "po rt"]).is_err());
+    /// assert!(Key::from_segments(["café"]).is_err());
+    /// assert!(Key::from_segments([]).is_err());
+    /// ```
+    pub fn from_segments(segments: impl IntoIterator<Item = &'a str>) -> Result<Self, ParseError> {
+        let segments = segments
+            .into_iter()
+            .map(Ident::new)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if segments.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        Ok(Self { segments })
+    }
+
     pub fn last_segment(&self) -> &Ident<'a> {
         self.segments
             .last()
@@ -28,22 +52,115 @@ impl<'a> Key<'a> {
             .expect("must have at least one segment")
     }
 
-    pub fn segments(&self) -> Segments {
+    pub fn segments(&self) -> Segments<'_> {
         Segments::from_path(self)
     }
 
+    pub fn starts_with(&self, prefix: &[Ident]) -> bool {
+        self.segments.starts_with(prefix)
+    }
+
+    /// Borrows the segments after `prefix`, if this key starts with it and is longer than it.
+    pub fn relative_to(&self, prefix: &[Ident]) -> Option<&[Ident<'a>]> {
+        (self.segments.len() > prefix.len() && self.starts_with(prefix))
+            .then(|| &self.segments[prefix.len()..])
+    }
+
+    /// Returns the segments after `prefix`, if this key starts with it and is longer than it.
+    pub fn strip_prefix(&self, prefix: &[Ident]) -> Option<Self> {
+        self.relative_to(prefix).map(|relative| Self {
+            segments: relative.to_vec(),
+        })
+    }
+
     pub fn var_name(&self) -> String {
-        let vec: Vec<String> = self.segments().map(|id| id.to_uppercase()).collect();
-        vec.join("_")
+        self.var_name_with(&NamingStrategy::DEFAULT)
+    }
+
+    pub fn var_name_with(&self, naming: &NamingStrategy) -> String {
+        naming.var_name(self.segments())
     }
 
-    pub fn chain(&'a self, next: &'a Self) -> Self {
+    pub fn chain(&self, next: &Self) -> Self {
         Self {
-            segments: self.segments().chain(next.segments()).collect(),
+            segments: self
+                .segments
+                .iter()
+                .chain(&next.segments)
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+impl<'a> Deref for Key<'a> {
+    type Target = [Ident<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.segments
+    }
+}
+
+/// Keys hash the same as their segments, so sets of keys can be searched by segments.
+impl<'a> Borrow<[Ident<'a>]> for Key<'a> {
+    fn borrow(&self) -> &[Ident<'a>] {
+        &self.segments
+    }
+}
+
+/// A key made of two borrowed parts, such as a table name and a field's key relative to it.
+///
+/// Unlike [`Key::chain`], this doesn't allocate.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyRef<'k, 'id> {
+    parent: &'k [Ident<'id>],
+    child: &'k [Ident<'id>],
+}
+
+impl<'k, 'id> KeyRef<'k, 'id> {
+    pub fn new(parent: &'k [Ident<'id>], child: &'k [Ident<'id>]) -> Self {
+        Self { parent, child }
+    }
+
+    pub fn segments(&self) -> impl Iterator<Item = Ident<'id>> + Clone + 'k {
+        self.parent.iter().chain(self.child).copied()
+    }
+
+    pub fn var_name(&self) -> String {
+        self.var_name_with(&NamingStrategy::DEFAULT)
+    }
+
+    pub fn var_name_with(&self, naming: &NamingStrategy) -> String {
+        naming.var_name(self.segments())
+    }
+
+    pub fn to_key(&self) -> Key<'id> {
+        Key {
+            segments: self.segments().collect(),
         }
     }
 }
 
+impl PartialEq<Key<'_>> for KeyRef<'_, '_> {
+    fn eq(&self, other: &Key<'_>) -> bool {
+        self.segments().eq(other.segments())
+    }
+}
+
+impl fmt::Display for KeyRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, segment) in self.segments().enumerate() {
+            if index != 0 {
+                Key::SEPARATOR.fmt(f)?;
+            }
+
+            segment.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl fmt::Display for Key<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.first_segment().fmt(f)?;
@@ -57,6 +174,7 @@ impl fmt::Display for Key<'_> {
     }
 }
 
+#[derive(Clone)]
 pub struct Segments<'path> {
     inner: std::slice::Iter<'path, Ident<'path>>,
 }
@@ -1,4 +1,6 @@
-use crate::{model::ident, Parse};
+use std::fmt;
+
+use crate::{model::ident, parser::Token, Parse};
 
 use super::Key;
 
@@ -7,33 +9,58 @@ impl<'p> Parse<'p> for Key<'p> {
     type Err = Error;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
-        if input.peek_char() == Some(Self::SEPARATOR) {
+        if input.peek_token() == Token::Dot {
             return Err(Error::LeadingSeparator);
         }
 
         let mut segments = vec![input.parse()?];
 
-        loop {
-            if !input.parse_char(Self::SEPARATOR) {
-                break;
-            } else {
-                segments.push(input.parse()?);
+        while input
+            .next_if(|next| next.token == Token::Dot && !next.after_space)
+            .is_some()
+        {
+            if input.peek().after_space {
+                return Err(Error::Ident(None));
             }
+
+            segments.push(input.parse()?);
         }
 
         Ok(Self { segments })
     }
 }
 
+impl<'p> Key<'p> {
+    /// Parses all of `s` as a key, rejecting anything after it, as for a basename.
+    pub(crate) fn parse_whole(s: &'p str) -> Option<Self> {
+        let mut input = crate::Parser::new(s);
+        let key = input.parse().ok()?;
+
+        (input.peek_token() == Token::Eof).then_some(key)
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
-    Ident,
+    Empty,
+    Ident(Option<ident::ParseError>),
     LeadingSeparator,
 }
 
 impl From<ident::ParseError> for Error {
-    fn from(_: ident::ParseError) -> Self {
-        Self::Ident
+    fn from(value: ident::ParseError) -> Self {
+        Self::Ident(Some(value))
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "expected a key"),
+            Self::Ident(Some(err)) => err.fmt(f),
+            Self::Ident(None) => write!(f, "expected a key after `.`"),
+            Self::LeadingSeparator => write!(f, "a key can't start with `.`"),
+        }
     }
 }
 
@@ -69,4 +96,27 @@ mod tests {
     test_invalid! {
         super::Key: "", " ", ".", "bip.", ".leading",
     }
+
+    #[test]
+    fn key_ref() {
+        let table = CONSTRUCTOR(&["db", "primary"]);
+        let field = CONSTRUCTOR(&["port"]);
+        let key_ref = super::super::KeyRef::new(&table, &field);
+
+        assert_eq!(key_ref.var_name(), "DB_PRIMARY_PORT");
+        assert_eq!(key_ref.to_string(), "db.primary.port");
+        assert_eq!(key_ref, table.chain(&field));
+    }
+
+    #[test]
+    fn strip_prefix() {
+        let key = CONSTRUCTOR(&["db", "primary", "port"]);
+
+        assert_eq!(
+            key.strip_prefix(&CONSTRUCTOR(&["db"])),
+            Some(CONSTRUCTOR(&["primary", "port"]))
+        );
+        assert_eq!(key.strip_prefix(&key), None);
+        assert_eq!(key.strip_prefix(&CONSTRUCTOR(&["primary"])), None);
+    }
 }
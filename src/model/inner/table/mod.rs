@@ -1,4 +1,6 @@
+use crate::model::key::KeyRef;
 use crate::Field;
+use crate::Ident;
 use crate::Set;
 use std::fmt;
 
@@ -8,21 +10,85 @@ pub use parse::Error as ParseError;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Table<'a> {
     name: crate::Key<'a>,
-    fields: Set<'a, Field<'a>>,
+    fields: Set<Field<'a>>,
 }
 
 impl<'a> Table<'a> {
+    /// Creates an empty table.
+    pub fn new(name: crate::Key<'a>) -> Self {
+        Self {
+            name,
+            fields: Set::new(),
+        }
+    }
+
     pub fn name(&self) -> &crate::Key<'a> {
         &self.name
     }
 
-    #[allow(dead_code)] // used in a test macro
-    pub(crate) fn new(name: crate::Key<'a>, fields: Set<'a, Field<'a>>) -> Self {
-        Self { name, fields }
+    /// Iterates over this table's fields, with the table name prepended to their keys.
+    pub fn fields(&self) -> Fields<'_, 'a> {
+        Fields::from_table(self)
+    }
+
+    /// Looks up a field by its key, relative to the table name.
+    pub fn get(&self, key: &[Ident]) -> Option<&crate::Value<'a>> {
+        self.fields.get(key).map(Field::value)
     }
 
-    pub fn fields(&self) -> Fields {
-        Fields::from_table(self)
+    /// Sets a field, relative to the table name.
+    ///
+    /// Returns the previous value, if there was one.
+    pub fn insert(
+        &mut self,
+        key: crate::Key<'a>,
+        value: crate::Value<'a>,
+    ) -> Option<crate::Value<'a>> {
+        self.fields
+            .replace(Field::new(key, value))
+            .map(|field| field.to_kv().1)
+    }
+
+    /// Removes a field by its key, relative to the table name.
+    pub fn remove(&mut self, key: &[Ident]) -> Option<crate::Value<'a>> {
+        self.fields.remove(key).map(|field| field.to_kv().1)
+    }
+
+    /// Iterates over this table's fields' full keys and mutable values.
+    pub(crate) fn values_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (KeyRef<'_, 'a>, &mut crate::Value<'a>)> {
+        let name = &self.name;
+
+        self.fields.iter_mut().map(move |field| {
+            let (key, value) = field.parts_mut();
+            (KeyRef::new(name, key), value)
+        })
+    }
+
+    /// Consumes this table, yielding its fields with the table name prepended to their keys.
+    pub fn into_fields(self) -> impl Iterator<Item = Field<'a>> {
+        let Self { name, fields } = self;
+
+        fields
+            .into_iter()
+            .map(move |field| field.with_parent(&name))
+    }
+
+    /// Moves this table under `prefix`, keeping its fields.
+    pub(crate) fn nest(self, prefix: &crate::Key<'a>) -> Self {
+        Self {
+            name: prefix.chain(&self.name),
+            fields: self.fields,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
     }
 }
 
@@ -30,7 +96,7 @@ impl fmt::Display for Table<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "[{}]", self.name())?;
 
-        for field in self.fields() {
+        for field in &self.fields {
             writeln!(f, "{}", field)?;
         }
 
@@ -38,13 +104,13 @@ impl fmt::Display for Table<'_> {
     }
 }
 
-pub struct Fields<'table> {
-    table_name: &'table crate::Key<'table>,
-    inner: <&'table Set<'table, Field<'table>> as IntoIterator>::IntoIter,
+pub struct Fields<'table, 'a> {
+    table_name: &'table crate::Key<'a>,
+    inner: crate::set::Iter<'table, Field<'a>>,
 }
 
-impl<'table> Fields<'table> {
-    fn from_table(table: &'table Table) -> Self {
+impl<'table, 'a> Fields<'table, 'a> {
+    fn from_table(table: &'table Table<'a>) -> Self {
         Self {
             table_name: table.name(),
             inner: table.fields.iter(),
@@ -52,18 +118,46 @@ impl<'table> Fields<'table> {
     }
 }
 
-impl<'table> Iterator for Fields<'table> {
-    type Item = Field<'table>;
+impl<'table, 'a> Iterator for Fields<'table, 'a> {
+    type Item = FieldRef<'table, 'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner
-            .next()
-            .map(|field| field.with_parent(self.table_name))
+        self.inner.next().map(|field| FieldRef {
+            key: KeyRef::new(self.table_name, field.key()),
+            value: field.value(),
+        })
+    }
+}
+
+/// A field of a [`Table`], with the table name prepended to its key without allocating.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldRef<'table, 'a> {
+    key: KeyRef<'table, 'a>,
+    value: &'table crate::Value<'a>,
+}
+
+impl<'table, 'a> FieldRef<'table, 'a> {
+    pub fn key(&self) -> KeyRef<'table, 'a> {
+        self.key
+    }
+
+    pub fn value(&self) -> &'table crate::Value<'a> {
+        self.value
+    }
+
+    pub fn to_field(&self) -> Field<'a> {
+        Field::new(self.key.to_key(), self.value.clone())
+    }
+}
+
+impl fmt::Display for FieldRef<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{key} = {value}", key = self.key(), value = self.value())
     }
 }
 
 impl crate::set::KeyEq for Table<'_> {
-    fn key(&self) -> &super::Key {
+    fn key(&self) -> &super::Key<'_> {
         self.name()
     }
 }
@@ -1,8 +1,14 @@
+use std::fmt;
+
 use crate::Set;
 
 use crate::{
-    model::{field, key},
-    Field, Parse,
+    model::{
+        field::{self, Entry},
+        key,
+    },
+    parser::Token,
+    Parse,
 };
 
 use super::Table;
@@ -30,44 +36,63 @@ impl Error {
     }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(Some(err)) => write!(f, "invalid table name: {err}"),
+            Self::Name(None) => write!(f, "expected a table name in `[...]`"),
+            Self::Field { source, .. } => source.fmt(f),
+            Self::MissingDelimiter => write!(f, "expected a new line"),
+            Self::DuplicateKey(key) => write!(f, "`{key}` is defined more than once"),
+        }
+    }
+}
+
 impl crate::Sealed for Table<'_> {}
 impl<'p> Parse<'p> for Table<'p> {
     type Err = Error;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
-        if !input.parse_char('[') {
+        if !input.parse_token(Token::LeftBracket) {
             return Err(Error::name());
         }
 
         let name = input.parse()?;
 
-        if !input.parse_char(']') {
+        if !input.parse_token(Token::RightBracket) {
             return Err(Error::name());
         }
 
+        // Unlike top-level fields, a table's fields may share a line with its header and each other,
+        // separated by spaces, as they always could.
+        if !input.at_delimiter() && !input.peek().after_space {
+            return Err(Error::MissingDelimiter);
+        }
+
         let mut fields = Set::new();
+        let mut field_counter = 0;
 
-        input
-            .parse_char_with(|ch| matches!(ch, '\n' | ' '))
-            .ok_or(Error::MissingDelimiter)?;
+        loop {
+            input.skip_trivia();
 
-        let mut field_counter = 0;
+            if matches!(input.peek_token(), Token::LeftBracket | Token::Eof) {
+                break;
+            }
 
-        while input
-            .peek_char()
-            .is_some_and(|ch| !matches!(ch, '[' | '\n'))
-        {
-            let field = input.parse::<Field>().map_err(|source| Error::Field {
+            let entry = input.parse::<Entry>().map_err(|source| Error::Field {
                 source,
                 index: field_counter,
             })?;
 
-            let key = field.key().to_string();
-            if !fields.insert(field) {
-                return Err(Error::DuplicateKey(key));
+            for field in entry.0 {
+                if let Err(field) = fields.try_insert(field) {
+                    return Err(Error::DuplicateKey(field.key().to_string()));
+                }
             }
 
-            input.parse_char_with(|ch| matches!(ch, '\n' | ' '));
+            if !input.at_delimiter() && !input.peek().after_space {
+                return Err(Error::MissingDelimiter);
+            }
 
             field_counter += 1;
         }
@@ -126,4 +151,15 @@ mod tests {
     test_invalid! {
         crate::Key: "", " ", ".", "bip.", ".leading",
     }
+
+    #[test]
+    fn invalid_table() {
+        crate::test_invalid::<Table>([
+            "",
+            "[]",
+            "[mongodb",
+            "[mongodb]port = 999",
+            "[mongodb]\nport = 999\nport = 1",
+        ]);
+    }
 }
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use super::{ParseError, Value};
+
+/// A calendar date, like `1979-05-27`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// A time of day, like `07:32:00.999`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    /// Up to 60, for leap seconds.
+    pub second: u8,
+    pub nanosecond: u32,
+}
+
+/// The offset from UTC of an offset date-time, like `Z` or `-07:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offset {
+    Utc,
+    Minutes(i16),
+}
+
+impl Date {
+    fn days_in_month(&self) -> u8 {
+        match self.month {
+            2 if self.year.is_multiple_of(4)
+                && (!self.year.is_multiple_of(100) || self.year.is_multiple_of(400)) =>
+            {
+                29
+            }
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Parses `YYYY-MM-DD` from the start of `s`.
+    fn parse(s: &[u8]) -> Result<Self, ParseError> {
+        if s.get(4) != Some(&b'-') || s.get(7) != Some(&b'-') {
+            return Err(ParseError::DateTime);
+        }
+
+        let date = Self {
+            year: digits(s, 0, 4)? as u16,
+            month: digits(s, 5, 2)? as u8,
+            day: digits(s, 8, 2)? as u8,
+        };
+
+        if (1..=12).contains(&date.month) && (1..=date.days_in_month()).contains(&date.day) {
+            Ok(date)
+        } else {
+            Err(ParseError::DateTime)
+        }
+    }
+}
+
+impl Time {
+    /// Parses `HH:MM:SS` and an optional fraction from the start of `s`, returning the time and its length.
+    fn parse(s: &[u8]) -> Result<(Self, usize), ParseError> {
+        if s.get(2) != Some(&b':') || s.get(5) != Some(&b':') {
+            return Err(ParseError::DateTime);
+        }
+
+        let mut time = Self {
+            hour: digits(s, 0, 2)? as u8,
+            minute: digits(s, 3, 2)? as u8,
+            second: digits(s, 6, 2)? as u8,
+            nanosecond: 0,
+        };
+
+        if time.hour > 23 || time.minute > 59 || time.second > 60 {
+            return Err(ParseError::DateTime);
+        }
+
+        let mut len = 8;
+
+        if s.get(len) == Some(&b'.') {
+            let fraction = s[len + 1..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+
+            if fraction == 0 {
+                return Err(ParseError::DateTime);
+            }
+
+            // Precision beyond nanoseconds is truncated.
+            let kept = fraction.min(9);
+            time.nanosecond = digits(s, len + 1, kept)? * 10u32.pow(9 - kept as u32);
+            len += 1 + fraction;
+        }
+
+        Ok((time, len))
+    }
+}
+
+impl Offset {
+    /// Parses `Z` or `±HH:MM` from the start of `s`, returning the offset and its length.
+    fn parse(s: &[u8]) -> Result<Option<(Self, usize)>, ParseError> {
+        let sign = match s.first() {
+            Some(b'Z' | b'z') => return Ok(Some((Self::Utc, 1))),
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            _ => return Ok(None),
+        };
+
+        if s.get(3) != Some(&b':') {
+            return Err(ParseError::DateTime);
+        }
+
+        let (hours, minutes) = (digits(s, 1, 2)?, digits(s, 4, 2)?);
+
+        if hours > 23 || minutes > 59 {
+            return Err(ParseError::DateTime);
+        }
+
+        Ok(Some((
+            Self::Minutes(sign * (hours * 60 + minutes) as i16),
+            6,
+        )))
+    }
+}
+
+/// Reads `len` ASCII digits starting at `at` as a number.
+fn digits(s: &[u8], at: usize, len: usize) -> Result<u32, ParseError> {
+    let digits = s.get(at..at + len).ok_or(ParseError::DateTime)?;
+
+    digits.iter().try_fold(0, |number, byte| match byte {
+        b'0'..=b'9' => Ok(number * 10 + u32::from(byte - b'0')),
+        _ => Err(ParseError::DateTime),
+    })
+}
+
+fn starts_with_digits(s: &[u8], len: usize) -> bool {
+    s.len() >= len && s[..len].iter().all(u8::is_ascii_digit)
+}
+
+/**
+Parses a date-time, date or time from the start of `s`, returning it and its length.
+
+Returns `None` if `s` doesn't start like one, so it can be parsed as something else, like an integer.
+A date and a time may be separated by `T` or a space, as in TOML.
+*/
+pub(super) fn parse(s: &str) -> Option<Result<(Value<'static>, usize), ParseError>> {
+    let s = s.as_bytes();
+
+    if starts_with_digits(s, 4) && s.get(4) == Some(&b'-') {
+        Some(parse_date_time(s))
+    } else if starts_with_digits(s, 2) && s.get(2) == Some(&b':') {
+        Some(Time::parse(s).map(|(time, len)| (Value::LocalTime(time), len)))
+    } else {
+        None
+    }
+}
+
+fn parse_date_time(s: &[u8]) -> Result<(Value<'static>, usize), ParseError> {
+    let date = Date::parse(s)?;
+
+    let has_time = match s.get(10) {
+        Some(b'T' | b't') => true,
+        Some(b' ') => starts_with_digits(&s[11..], 2) && s.get(13) == Some(&b':'),
+        _ => false,
+    };
+
+    if !has_time {
+        return Ok((Value::LocalDate(date), 10));
+    }
+
+    let (time, len) = Time::parse(&s[11..])?;
+    let len = 11 + len;
+
+    Ok(match Offset::parse(&s[len..])? {
+        Some((offset, offset_len)) => (Value::OffsetDateTime(date, time, offset), len + offset_len),
+        None => (Value::LocalDateTime(date, time), len),
+    })
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+
+        if self.nanosecond != 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Utc => write!(f, "Z"),
+            Self::Minutes(minutes) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Date, Offset, Time};
+    use crate::Value;
+
+    const DATE: Date = Date {
+        year: 1979,
+        month: 5,
+        day: 27,
+    };
+
+    const TIME: Time = Time {
+        hour: 7,
+        minute: 32,
+        second: 0,
+        nanosecond: 0,
+    };
+
+    #[test]
+    fn valid() {
+        crate::test_valid(
+            |input| input,
+            [
+                (
+                    "1979-05-27T07:32:00Z",
+                    Value::OffsetDateTime(DATE, TIME, Offset::Utc),
+                ),
+                (
+                    "1979-05-27 07:32:00-07:00",
+                    Value::OffsetDateTime(DATE, TIME, Offset::Minutes(-420)),
+                ),
+                ("1979-05-27t07:32:00", Value::LocalDateTime(DATE, TIME)),
+                ("1979-05-27", Value::LocalDate(DATE)),
+                ("07:32:00", Value::LocalTime(TIME)),
+                (
+                    "00:32:00.999999",
+                    Value::LocalTime(Time {
+                        hour: 0,
+                        nanosecond: 999_999_000,
+                        ..TIME
+                    }),
+                ),
+                (
+                    "2024-02-29",
+                    Value::LocalDate(Date {
+                        year: 2024,
+                        month: 2,
+                        day: 29,
+                    }),
+                ),
+            ],
+        );
+    }
+
+    test_invalid! {
+        Value:
+            "1979-13-27",
+            "2023-02-29",
+            "1979-05-27T25:00:00",
+            "1979-05-27T07:32",
+            "1979-05-27T07:32:00.",
+            "1979-05-27T07:32:00+7:00",
+            "07:60:00",
+            "1979-5-27"
+    }
+
+    #[test]
+    fn rfc_3339() {
+        for (input, var) in [
+            ("1979-05-27 07:32:00z", "1979-05-27T07:32:00Z"),
+            (
+                "1979-05-27T07:32:00.500+05:30",
+                "1979-05-27T07:32:00.5+05:30",
+            ),
+            (
+                "1979-05-27T00:32:00.123456789123",
+                "1979-05-27T00:32:00.123456789",
+            ),
+            ("1979-05-27", "1979-05-27"),
+        ] {
+            let value = crate::test_utils::parse_str::<Value>(input);
+
+            assert_eq!(value.var(), var);
+            assert_eq!(value.to_string(), var);
+        }
+    }
+}
@@ -1,19 +1,94 @@
-use std::fmt;
+use std::{borrow::Cow, fmt, time::Duration};
 
 mod parse;
 pub use parse::Error as ParseError;
 
-#[derive(Hash, Clone, Debug, PartialEq, Eq, Copy)]
+mod datetime;
+pub use datetime::{Date, Offset, Time};
+
+mod units;
+pub use units::{DurationUnit, SizeUnit};
+
+#[derive(Hash, Clone, Debug, PartialEq, Eq)]
 pub enum Value<'s> {
     String(&'s str),
     Integer(u64),
+    /// A list of strings and integers, which expands to a single variable with its items joined by a delimiter.
+    Array(Vec<Value<'s>>),
+    OffsetDateTime(Date, Time, Offset),
+    LocalDateTime(Date, Time),
+    LocalDate(Date),
+    LocalTime(Time),
+    /// A duration literal like `1m30s`.
+    Duration(Duration),
+    /// A size literal like `512MiB`, in bytes.
+    Size(u64),
+    /**
+    A path, written `@path("./data")`.
+
+    When loaded from a file, relative paths are resolved against the file's directory
+    and a leading `~` is expanded to the home directory, so the variable holds an absolute path.
+    */
+    Path(Cow<'s, str>),
+    /**
+    The contents of a file, written `@file("secrets/db_password")`.
+
+    Loading a file replaces it with a [`String`](Self::String) of the referenced file's contents,
+    read relative to the file's directory. Until then, it expands to the path.
+    */
+    File(Cow<'s, str>),
+}
+
+/// How [`Value::var_with`] writes values that have more than one sensible form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Format<'f> {
+    /// The string array items are joined with.
+    pub delimiter: &'f str,
+    pub duration: DurationUnit,
+    pub size: SizeUnit,
+}
+
+impl Format<'static> {
+    pub const DEFAULT: Self = Self {
+        delimiter: ",",
+        duration: DurationUnit::Milliseconds,
+        size: SizeUnit::Bytes,
+    };
+}
+
+impl Default for Format<'static> {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 impl<'s> Value<'s> {
     pub fn var(&self) -> String {
+        self.var_with(&Format::DEFAULT)
+    }
+
+    /// Like [`var`](Self::var), but writes arrays, durations and sizes according to `format`.
+    pub fn var_with(&self, format: &Format) -> String {
         match self {
             Self::String(s) => (*s).to_owned(),
+            Self::Path(path) | Self::File(path) => path.clone().into_owned(),
             Self::Integer(int) => int.to_string(),
+            Self::Array(items) => {
+                let mut var = String::new();
+
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        var.push_str(format.delimiter);
+                    }
+
+                    var.push_str(&item.var_with(format));
+                }
+
+                var
+            }
+            Self::Duration(duration) => format.duration.format(*duration),
+            Self::Size(bytes) => format.size.format(*bytes),
+            _ => self.to_string(),
         }
     }
 }
@@ -23,6 +98,27 @@ impl fmt::Display for Value<'_> {
         match self {
             Self::String(s) => write!(f, "\"{}\"", s),
             Self::Integer(int) => int.fmt(f),
+            Self::Array(items) => {
+                write!(f, "[")?;
+
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    item.fmt(f)?;
+                }
+
+                write!(f, "]")
+            }
+            Self::OffsetDateTime(date, time, offset) => write!(f, "{date}T{time}{offset}"),
+            Self::LocalDateTime(date, time) => write!(f, "{date}T{time}"),
+            Self::LocalDate(date) => date.fmt(f),
+            Self::LocalTime(time) => time.fmt(f),
+            Self::Duration(duration) => units::fmt_duration(*duration, f),
+            Self::Size(bytes) => units::fmt_size(*bytes, f),
+            Self::Path(path) => write!(f, "@path(\"{}\")", path),
+            Self::File(path) => write!(f, "@file(\"{}\")", path),
         }
     }
 }
@@ -1,6 +1,9 @@
-use std::num::ParseIntError;
+use std::{fmt, num::ParseIntError};
 
-use crate::Parse;
+use crate::{
+    parser::{Lexeme, Token},
+    Parse,
+};
 
 use super::Value;
 
@@ -9,6 +12,16 @@ pub enum Error {
     EmptyInput,
     ParseInt(ParseIntError),
     UnclosedString,
+    /// An array is missing a `,` between items or its closing `]`.
+    UnclosedArray,
+    /// Arrays can't contain arrays, since they expand to a single flat variable.
+    NestedArray,
+    /// A date or time that is malformed or out of range, like `2023-02-29`.
+    DateTime,
+    /// A malformed duration or size, like `1m1m` or `5mib`.
+    Unit,
+    /// A value starting with `@` that isn't a well-formed `@path("...")` or `@file("...")`.
+    Reference,
 }
 
 impl From<ParseIntError> for Error {
@@ -17,19 +30,135 @@ impl From<ParseIntError> for Error {
     }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyInput => write!(f, "expected a value"),
+            Self::ParseInt(err) => write!(f, "invalid integer: {err}"),
+            Self::UnclosedString => write!(f, "unclosed string"),
+            Self::UnclosedArray => write!(f, "expected `,` or `]` in array"),
+            Self::NestedArray => write!(f, "arrays can't contain arrays"),
+            Self::DateTime => write!(f, "invalid date or time"),
+            Self::Unit => write!(f, "invalid duration or size"),
+            Self::Reference => write!(f, "expected `@path(\"...\")` or `@file(\"...\")`"),
+        }
+    }
+}
+
 impl crate::Sealed for Value<'_> {}
 impl<'p> Parse<'p> for Value<'p> {
     type Err = Error;
 
     fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
-        if input.peek_char() == Some('"') {
-            input.next_char();
+        match input.peek_token() {
+            Token::String(s) => {
+                input.next_lexeme();
+                Ok(Self::String(s))
+            }
+            Token::UnclosedString => Err(Error::UnclosedString),
+            Token::Bare(_) => match super::datetime::parse(input.rest()) {
+                Some(datetime) => {
+                    let (value, len) = datetime?;
+                    input.skip(len);
+                    Ok(value)
+                }
+                None => match input.peek_token() {
+                    Token::Bare(literal)
+                        if literal.starts_with(|ch: char| ch.is_ascii_digit())
+                            && !literal.bytes().all(|byte| byte.is_ascii_digit()) =>
+                    {
+                        input.next_lexeme();
+                        super::units::parse(literal)
+                    }
+                    _ => Ok(Self::Integer(input.parse()?)),
+                },
+            },
+            Token::LeftBracket => Ok(Self::Array(input.parse::<Array>()?.0)),
+            Token::At => match input.parse::<Reference>()? {
+                Reference("path", path) => Ok(Self::Path(path.into())),
+                Reference("file", path) => Ok(Self::File(path.into())),
+                _ => Err(Error::Reference),
+            },
+            _ => Err(Error::EmptyInput),
+        }
+    }
+}
+
+/// `@name("argument")`, a value that refers to something outside the document.
+struct Reference<'p>(&'p str, &'p str);
+
+impl crate::Sealed for Reference<'_> {}
+impl<'p> Parse<'p> for Reference<'p> {
+    type Err = Error;
+
+    fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
+        let adjacent =
+            |token: Token<'p>| move |next: &Lexeme<'p>| next.token == token && !next.after_space;
+
+        if !input.parse_token(Token::At) {
+            return Err(Error::Reference);
+        }
+
+        let name = match input.next_lexeme() {
+            Lexeme {
+                token: Token::Bare(name),
+                after_space: false,
+                ..
+            } => name,
+            _ => return Err(Error::Reference),
+        };
+
+        if input.next_if(adjacent(Token::LeftParen)).is_none() {
+            return Err(Error::Reference);
+        }
+
+        let argument = match input.next_lexeme().token {
+            Token::String(argument) if !argument.is_empty() => argument,
+            _ => return Err(Error::Reference),
+        };
+
+        if input.parse_token(Token::RightParen) {
+            Ok(Self(name, argument))
+        } else {
+            Err(Error::Reference)
+        }
+    }
+}
+
+/// `[item, item]`, which may span lines and end with a trailing comma.
+struct Array<'p>(Vec<Value<'p>>);
+
+impl crate::Sealed for Array<'_> {}
+impl<'p> Parse<'p> for Array<'p> {
+    type Err = Error;
+
+    fn parse(input: &mut crate::Parser<'p>) -> Result<Self, Self::Err> {
+        if !input.parse_token(Token::LeftBracket) {
+            return Err(Error::EmptyInput);
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            input.skip_trivia();
+
+            match input.peek_token() {
+                Token::RightBracket => break,
+                Token::LeftBracket => return Err(Error::NestedArray),
+                _ => items.push(input.parse()?),
+            }
+
+            input.skip_trivia();
+
+            if !input.parse_token(Token::Comma) {
+                break;
+            }
+        }
 
-            let s = input.parse_while(|ch| ch != &'"').unwrap_or_default();
-            input.next_char();
-            Ok(Self::String(s))
+        if input.parse_token(Token::RightBracket) {
+            Ok(Self(items))
         } else {
-            Ok(Self::Integer(input.parse()?))
+            Err(Error::UnclosedArray)
         }
     }
 }
@@ -38,7 +167,7 @@ impl<'p> Parse<'p> for Value<'p> {
 mod tests {
     use super::Value;
 
-    fn string(s: &str) -> Value {
+    fn string(s: &str) -> Value<'_> {
         Value::String(s)
     }
 
@@ -46,6 +175,10 @@ mod tests {
         Value::Integer(int)
     }
 
+    fn array<'s>(items: impl IntoIterator<Item = Value<'s>>) -> Value<'s> {
+        Value::Array(items.into_iter().collect())
+    }
+
     #[test]
     fn valid() {
         crate::test_valid(
@@ -54,11 +187,43 @@ mod tests {
                 (r#""preen""#, string("preen")),
                 ("105", integer(105)),
                 ("999", integer(999)),
+                ("[]", array([])),
+                (r#"["a", 2]"#, array([string("a"), integer(2)])),
+                (
+                    "[\n  \"a\", # first\n  \"b\",\n]",
+                    array([string("a"), string("b")]),
+                ),
             ],
         );
     }
 
+    #[test]
+    fn path() {
+        let value = crate::test_utils::parse_str::<Value>("@path(\"./data\")");
+
+        assert_eq!(value, Value::Path("./data".into()));
+        assert_eq!(value.to_string(), "@path(\"./data\")");
+
+        crate::test_invalid::<Value>([
+            "@path(./data)",
+            "@path (\"./data\")",
+            "@path(\"\")",
+            "@path(\"./data\"",
+            "@nothing(\"./data\")",
+        ]);
+    }
+
+    #[test]
+    fn file() {
+        let value = crate::test_utils::parse_str::<Value>("@file(\"secrets/db_password\")");
+
+        assert_eq!(value, Value::File("secrets/db_password".into()));
+        assert_eq!(value.to_string(), "@file(\"secrets/db_password\")");
+        crate::test_invalid::<Value>(["@file()", "@file(secrets)"]);
+    }
+
     test_invalid! {
-        Value: "", "preen", " "
+        Value: "", "preen", " ", "\"preen", "\"pr\neen\"",
+            "[", "[\"a\"", "[\"a\" \"b\"]", "[,]", "[[1]]", "[1,,2]"
     }
 }
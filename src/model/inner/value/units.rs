@@ -0,0 +1,238 @@
+use std::{fmt, time::Duration};
+
+use super::{ParseError, Value};
+
+/// The unit [`Value::Duration`]s expand to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationUnit {
+    Nanoseconds,
+    Microseconds,
+    #[default]
+    Milliseconds,
+    Seconds,
+}
+
+impl DurationUnit {
+    fn nanos(self) -> u128 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+        }
+    }
+
+    /// Writes `duration` as a number of this unit, with a fraction if it isn't a whole number.
+    pub fn format(self, duration: Duration) -> String {
+        ratio(duration.as_nanos(), self.nanos())
+    }
+}
+
+/// The unit [`Value::Size`]s expand to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SizeUnit {
+    #[default]
+    Bytes,
+    Kibibytes,
+    Mebibytes,
+}
+
+impl SizeUnit {
+    fn bytes(self) -> u128 {
+        match self {
+            Self::Bytes => 1,
+            Self::Kibibytes => 1 << 10,
+            Self::Mebibytes => 1 << 20,
+        }
+    }
+
+    /// Writes `bytes` as a number of this unit, with a fraction if it isn't a whole number.
+    pub fn format(self, bytes: u64) -> String {
+        ratio(bytes.into(), self.bytes())
+    }
+}
+
+/// Writes `value / unit` in decimal, without trailing zeros.
+fn ratio(value: u128, unit: u128) -> String {
+    let (whole, mut rest) = (value / unit, value % unit);
+    let mut s = whole.to_string();
+
+    if rest != 0 {
+        s.push('.');
+
+        // Every unit is a power of 2 or 10, so the fraction always terminates.
+        while rest != 0 {
+            rest *= 10;
+            s.push(char::from(b'0' + (rest / unit) as u8));
+            rest %= unit;
+        }
+    }
+
+    s
+}
+
+/// Duration units, largest first, as a literal's components must be.
+const DURATION_UNITS: [(&str, u64); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+const SIZE_UNITS: [(&str, u64); 9] = [
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("KB", 1_000),
+    ("B", 1),
+];
+
+/// Splits `s` into a leading number and whatever follows it.
+fn split_number(s: &str) -> Result<(u64, &str), ParseError> {
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+
+    if digits == 0 {
+        return Err(ParseError::Unit);
+    }
+
+    let number = s[..digits].parse().map_err(|_| ParseError::Unit)?;
+
+    Ok((number, &s[digits..]))
+}
+
+/**
+Parses a duration like `1m30s` or a size like `512MiB`.
+
+A duration's components go from largest to smallest unit, each at most once:
+`d`, `h`, `m`, `s`, `ms`, `us` and `ns`. A size is a single number followed by `B`,
+a decimal unit like `MB`, or a binary unit like `MiB`.
+*/
+pub(super) fn parse(s: &str) -> Result<Value<'static>, ParseError> {
+    let (number, unit) = split_number(s)?;
+
+    if let Some(&(_, bytes)) = SIZE_UNITS.iter().find(|(name, _)| *name == unit) {
+        return number
+            .checked_mul(bytes)
+            .map(Value::Size)
+            .ok_or(ParseError::Unit);
+    }
+
+    let mut nanos: u128 = 0;
+    let mut units = DURATION_UNITS.iter();
+    let mut rest = s;
+
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest)?;
+        let unit_len = after.bytes().take_while(u8::is_ascii_alphabetic).count();
+        let unit = &after[..unit_len];
+
+        // Searching the remaining units rejects repeated and out-of-order components.
+        let &(_, unit_nanos) = units
+            .find(|(name, _)| *name == unit)
+            .ok_or(ParseError::Unit)?;
+
+        nanos += u128::from(number) * u128::from(unit_nanos);
+        rest = &after[unit_len..];
+    }
+
+    let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| ParseError::Unit)?;
+
+    Ok(Value::Duration(Duration::new(
+        secs,
+        (nanos % 1_000_000_000) as u32,
+    )))
+}
+
+/// Writes a duration as a literal, like `1m30s`.
+pub(super) fn fmt_duration(duration: Duration, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let mut nanos = duration.as_nanos();
+
+    if nanos == 0 {
+        return write!(f, "0s");
+    }
+
+    for (name, unit) in DURATION_UNITS {
+        let unit = u128::from(unit);
+
+        if nanos >= unit {
+            write!(f, "{}{name}", nanos / unit)?;
+            nanos %= unit;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a size as a literal, in the largest unit that divides it evenly.
+pub(super) fn fmt_size(bytes: u64, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (name, unit) = SIZE_UNITS
+        .into_iter()
+        .find(|(_, unit)| bytes != 0 && bytes.is_multiple_of(*unit))
+        .unwrap_or(("B", 1));
+
+    write!(f, "{}{name}", bytes / unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DurationUnit, SizeUnit};
+    use crate::Value;
+
+    #[test]
+    fn valid() {
+        crate::test_valid(
+            |input| input,
+            [
+                ("30s", Value::Duration(Duration::from_secs(30))),
+                ("1m30s", Value::Duration(Duration::from_secs(90))),
+                ("1d12h", Value::Duration(Duration::from_secs(129_600))),
+                ("250ms", Value::Duration(Duration::from_millis(250))),
+                ("1s500us", Value::Duration(Duration::from_micros(1_000_500))),
+                ("512MiB", Value::Size(512 << 20)),
+                ("2KB", Value::Size(2_000)),
+                ("0B", Value::Size(0)),
+            ],
+        );
+    }
+
+    test_invalid! {
+        Value: "30x", "1s1m", "1m1m", "s", "1m30", "512mib", "5MiB1s", "30000000000000000000000d"
+    }
+
+    #[test]
+    fn canonical() {
+        for (input, literal, var) in [
+            ("90s", "1m30s", "90000"),
+            ("1500ms", "1s500ms", "1500"),
+            ("0s", "0s", "0"),
+            ("512MiB", "512MiB", "536870912"),
+            ("1500B", "1500B", "1500"),
+            ("3000KB", "3MB", "3000000"),
+        ] {
+            let value = crate::test_utils::parse_str::<Value>(input);
+
+            assert_eq!(value.to_string(), literal);
+            assert_eq!(value.var(), var);
+        }
+
+        assert_eq!(
+            DurationUnit::Seconds.format(Duration::from_millis(1500)),
+            "1.5"
+        );
+        assert_eq!(
+            DurationUnit::Nanoseconds.format(Duration::from_micros(2)),
+            "2000"
+        );
+        assert_eq!(SizeUnit::Kibibytes.format(1536), "1.5");
+        assert_eq!(SizeUnit::Mebibytes.format(1 << 20), "1");
+    }
+}
@@ -0,0 +1,155 @@
+use std::borrow::Cow;
+
+use crate::Ident;
+
+/// How [`NamingStrategy`] cases the segments of variable names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Case {
+    /// `api_key` becomes `API_KEY`.
+    #[default]
+    Upper,
+
+    /// `API_KEY` becomes `api_key`.
+    Lower,
+
+    /// Segments keep the case they're written in.
+    Preserve,
+}
+
+/**
+How keys are turned into environment variable names.
+
+The [default](NamingStrategy::DEFAULT) uppercases each key segment, joins segments with `_`,
+and prepends the document's basename. In every strategy, hyphens in segments become underscores.
+How values are written is up to [`ValueFormat`](crate::ValueFormat).
+
+```
+# use nvee::{Case, Key, NamingStrategy};
+let key = Key::from_segments(["logging", "logLevel"]).unwrap();
+
+assert_eq!(key.var_name(), "LOGGING_LOGLEVEL");
+
+let dotnet = NamingStrategy::new().separator("__").case(Case::Preserve);
+assert_eq!(key.var_name_with(&dotnet), "logging__logLevel");
+
+let prefixed = NamingStrategy::new().prefix("MYAPP_");
+assert_eq!(key.var_name_with(&prefixed), "MYAPP_LOGGING_LOGLEVEL");
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingStrategy {
+    separator: Cow<'static, str>,
+    case: Case,
+    prefix: Option<Cow<'static, str>>,
+    basename: bool,
+}
+
+impl Default for NamingStrategy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl NamingStrategy {
+    pub const DEFAULT: Self = Self {
+        separator: Cow::Borrowed("_"),
+        case: Case::Upper,
+        prefix: None,
+        basename: true,
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the string placed between key segments, `_` by default.
+    pub fn separator(mut self, separator: impl Into<Cow<'static, str>>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    pub fn case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Sets a string prepended verbatim to every variable name, before the basename.
+    pub fn prefix(mut self, prefix: impl Into<Cow<'static, str>>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Sets whether documents' basenames are prepended to their variable names, which they are by default.
+    pub fn basename(mut self, basename: bool) -> Self {
+        self.basename = basename;
+        self
+    }
+
+    pub fn uses_basename(&self) -> bool {
+        self.basename
+    }
+
+    /// Joins key segments into a variable name, in a single allocation.
+    pub fn var_name<'id>(&self, segments: impl IntoIterator<Item = Ident<'id>> + Clone) -> String {
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        let len = segments
+            .clone()
+            .into_iter()
+            .map(|segment| segment.len() + self.separator.len())
+            .sum::<usize>()
+            + prefix.len();
+
+        let mut name = String::with_capacity(len);
+        name.push_str(prefix);
+
+        for (index, segment) in segments.into_iter().enumerate() {
+            if index != 0 {
+                name.push_str(&self.separator);
+            }
+
+            self.push_segment(segment, &mut name);
+        }
+
+        name
+    }
+
+    fn push_segment(&self, segment: Ident, name: &mut String) {
+        let normalize = |ch: char| if ch == '-' { '_' } else { ch };
+
+        match self.case {
+            Case::Upper if segment.is_ascii() => {
+                name.extend(segment.chars().map(|ch| normalize(ch.to_ascii_uppercase())))
+            }
+            Case::Upper => name.extend(segment.chars().flat_map(char::to_uppercase).map(normalize)),
+            Case::Lower => name.extend(segment.chars().flat_map(char::to_lowercase).map(normalize)),
+            Case::Preserve => name.extend(segment.chars().map(normalize)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Case, NamingStrategy};
+    use crate::model::key::CONSTRUCTOR as key;
+
+    #[test]
+    fn strategies() {
+        let service_key = key(&["my-service", "apiKey"]);
+
+        for (strategy, name) in [
+            (NamingStrategy::DEFAULT, "MY_SERVICE_APIKEY"),
+            (NamingStrategy::new().separator("__"), "MY_SERVICE__APIKEY"),
+            (NamingStrategy::new().case(Case::Lower), "my_service_apikey"),
+            (
+                NamingStrategy::new().case(Case::Preserve),
+                "my_service_apiKey",
+            ),
+            (
+                NamingStrategy::new().prefix("APP_"),
+                "APP_MY_SERVICE_APIKEY",
+            ),
+        ] {
+            assert_eq!(service_key.var_name_with(&strategy), name);
+        }
+    }
+}
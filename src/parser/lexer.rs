@@ -0,0 +1,254 @@
+/// A single token of nvee syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'i> {
+    Newline,
+    /// A comment, without the leading `#`.
+    Comment(&'i str),
+    LeftBracket,
+    RightBracket,
+    LeftBrace,
+    RightBrace,
+    LeftParen,
+    RightParen,
+    Dot,
+    Comma,
+    Equals,
+    At,
+    /// A run of bare key characters, which is either a key segment or an integer.
+    Bare(&'i str),
+    /// A string's contents, without the quotes.
+    String(&'i str),
+    /// A string missing its closing quote before the end of the line.
+    UnclosedString,
+    Invalid(char),
+    Eof,
+}
+
+/// A [`Token`] with its position in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lexeme<'i> {
+    pub token: Token<'i>,
+    /// Byte offset of the start of the token.
+    pub start: usize,
+    /// Byte offset of the end of the token.
+    pub end: usize,
+    /// Whether the token is preceded by spaces or tabs on the same line.
+    pub after_space: bool,
+}
+
+pub const BARE_CHAR: fn(char) -> bool = |ch| ch.is_ascii() && is_bare_byte(ch as u8);
+
+fn is_bare_byte(byte: u8) -> bool {
+    matches!(byte, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-')
+}
+
+/**
+Splits input into [`Lexeme`]s, ending with [`Token::Eof`] forever.
+
+Every byte that delimits a token is ASCII, so the input is scanned byte by byte;
+since UTF-8 continuation bytes are never ASCII, token boundaries always fall on `char` boundaries.
+*/
+#[derive(Debug, Clone)]
+pub struct Lexer<'i> {
+    input: &'i str,
+    offset: usize,
+}
+
+impl<'i> Lexer<'i> {
+    pub fn new(input: &'i str) -> Self {
+        Self { input, offset: 0 }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The input from `offset` on.
+    pub fn rest_from(&self, offset: usize) -> &'i str {
+        &self.input[offset..]
+    }
+
+    /// Moves to `offset`, which must be at a char boundary.
+    pub fn seek(&mut self, offset: usize) {
+        self.offset = offset;
+    }
+
+    fn peek_byte(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.offset).copied()
+    }
+
+    /// Advances past the longest run of bytes matching `matches`, returning it.
+    fn take_while(&mut self, matches: impl Fn(u8) -> bool) -> &'i str {
+        let start = self.offset;
+        let rest = &self.input.as_bytes()[start..];
+
+        self.offset += rest
+            .iter()
+            .position(|&byte| !matches(byte))
+            .unwrap_or(rest.len());
+
+        &self.input[start..self.offset]
+    }
+
+    pub fn next_lexeme(&mut self) -> Lexeme<'i> {
+        let after_space = !self
+            .take_while(|byte| matches!(byte, b' ' | b'\t'))
+            .is_empty();
+        let start = self.offset;
+
+        let Some(byte) = self.peek_byte() else {
+            return Lexeme {
+                token: Token::Eof,
+                start,
+                end: start,
+                after_space,
+            };
+        };
+
+        self.offset += 1;
+
+        let token = match byte {
+            b'\n' => Token::Newline,
+            b'\r' if self.peek_byte() == Some(b'\n') => {
+                self.offset += 1;
+                Token::Newline
+            }
+            b'#' => Token::Comment(self.take_while(|byte| !matches!(byte, b'\n' | b'\r'))),
+            b'[' => Token::LeftBracket,
+            b']' => Token::RightBracket,
+            b'{' => Token::LeftBrace,
+            b'}' => Token::RightBrace,
+            b'(' => Token::LeftParen,
+            b')' => Token::RightParen,
+            b'.' => Token::Dot,
+            b',' => Token::Comma,
+            b'=' => Token::Equals,
+            b'@' => Token::At,
+            b'"' => {
+                let contents = self.take_while(|byte| !matches!(byte, b'"' | b'\n' | b'\r'));
+
+                if self.peek_byte() == Some(b'"') {
+                    self.offset += 1;
+                    Token::String(contents)
+                } else {
+                    Token::UnclosedString
+                }
+            }
+            byte if is_bare_byte(byte) => {
+                self.offset = start;
+                Token::Bare(self.take_while(is_bare_byte))
+            }
+            _ => {
+                let ch = self.input[start..]
+                    .chars()
+                    .next()
+                    .expect("offset should be at a char boundary");
+
+                self.offset = start + ch.len_utf8();
+                Token::Invalid(ch)
+            }
+        };
+
+        Lexeme {
+            token,
+            start,
+            end: self.offset,
+            after_space,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lexer, Token};
+
+    fn tokens(input: &str) -> Vec<Token<'_>> {
+        let mut lexer = Lexer::new(input);
+        let mut tokens = Vec::new();
+
+        loop {
+            match lexer.next_lexeme().token {
+                Token::Eof => return tokens,
+                token => tokens.push(token),
+            }
+        }
+    }
+
+    #[test]
+    fn valid() {
+        use Token::*;
+
+        pretty_assertions::assert_eq!(
+            tokens(
+                "# config\r\n[db.primary]\nurl = \"https://example.com\" # inline\nport = 2020\n"
+            ),
+            [
+                Comment(" config"),
+                Newline,
+                LeftBracket,
+                Bare("db"),
+                Dot,
+                Bare("primary"),
+                RightBracket,
+                Newline,
+                Bare("url"),
+                Equals,
+                String("https://example.com"),
+                Comment(" inline"),
+                Newline,
+                Bare("port"),
+                Equals,
+                Bare("2020"),
+                Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        use Token::*;
+
+        pretty_assertions::assert_eq!(
+            tokens("word = \"jaunt\nkey = ?"),
+            [
+                Bare("word"),
+                Equals,
+                UnclosedString,
+                Newline,
+                Bare("key"),
+                Equals,
+                Invalid('?')
+            ]
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        use Token::*;
+
+        pretty_assertions::assert_eq!(
+            tokens("word = \"jäünt\" # ünïcödé\nkey = ü"),
+            [
+                Bare("word"),
+                Equals,
+                String("jäünt"),
+                Comment(" ünïcödé"),
+                Newline,
+                Bare("key"),
+                Equals,
+                Invalid('ü')
+            ]
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let mut lexer = Lexer::new("a  = 1");
+
+        let a = lexer.next_lexeme();
+        assert_eq!((a.start, a.end, a.after_space), (0, 1, false));
+
+        let equals = lexer.next_lexeme();
+        assert_eq!((equals.start, equals.end, equals.after_space), (3, 4, true));
+    }
+}
@@ -1,57 +1,89 @@
+use std::fmt;
+
+pub mod lexer;
+pub use lexer::{Lexeme, Lexer, Token};
+
 pub struct Parser<'i> {
-    input: &'i str,
+    lexer: Lexer<'i>,
+    peeked: Option<Lexeme<'i>>,
 }
 
 impl<'i> Parser<'i> {
     pub(super) fn new(input: &'i str) -> Self {
-        Self { input }
+        Self {
+            lexer: Lexer::new(input),
+            peeked: None,
+        }
     }
 
-    pub fn peek_char(&self) -> Option<char> {
-        self.input.chars().next()
+    pub fn peek(&mut self) -> Lexeme<'i> {
+        *self.peeked.get_or_insert_with(|| self.lexer.next_lexeme())
     }
 
-    pub fn next_char(&mut self) -> Option<char> {
-        let ch = self.peek_char()?;
-        self.input = &self.input[ch.len_utf8()..];
-        Some(ch)
+    pub fn peek_token(&mut self) -> Token<'i> {
+        self.peek().token
     }
 
-    pub fn parse_char(&mut self, ch: char) -> bool {
-        self.parse_char_with(|next| next == ch).is_some()
+    pub fn next_lexeme(&mut self) -> Lexeme<'i> {
+        self.peeked
+            .take()
+            .unwrap_or_else(|| self.lexer.next_lexeme())
     }
 
-    pub fn parse_char_with(&mut self, matches: impl FnOnce(char) -> bool) -> Option<char> {
-        if let Some(next) = self.peek_char() {
-            if matches(next) {
-                self.next_char();
-                return Some(next);
-            }
+    /// Consumes the next lexeme if it matches.
+    pub fn next_if(&mut self, matches: impl FnOnce(&Lexeme<'i>) -> bool) -> Option<Lexeme<'i>> {
+        let next = self.peek();
+
+        if matches(&next) {
+            self.peeked = None;
+            Some(next)
+        } else {
+            None
         }
+    }
 
-        None
+    /// Consumes the next token if it's `token`.
+    pub fn parse_token(&mut self, token: Token) -> bool {
+        self.next_if(|next| next.token == token).is_some()
     }
 
-    pub fn parse<P: Parse<'i>>(&mut self) -> Result<P, P::Err> {
-        P::parse(self)
+    /// Byte offset of the next lexeme.
+    pub fn offset(&mut self) -> usize {
+        self.peek().start
     }
 
-    pub fn parse_while(&mut self, matches: impl FnMut(&char) -> bool) -> Option<&'i str> {
-        let mut counter = 0;
+    /// The raw input from the start of the next lexeme on, for values that don't fit the token grammar.
+    pub fn rest(&mut self) -> &'i str {
+        let start = self.offset();
+        self.lexer.rest_from(start)
+    }
 
-        for ch in self.input.chars().take_while(matches) {
-            counter += ch.len_utf8();
-        }
+    /// Consumes `len` bytes of [`rest`](Self::rest), ignoring token boundaries.
+    pub fn skip(&mut self, len: usize) {
+        let start = self.offset();
+        self.peeked = None;
+        self.lexer.seek(start + len);
+    }
 
-        if counter != 0 {
-            let output = &self.input[..counter];
+    /// Skips newlines and comments.
+    pub fn skip_trivia(&mut self) {
+        while self
+            .next_if(|next| matches!(next.token, Token::Newline | Token::Comment(_)))
+            .is_some()
+        {}
+    }
 
-            self.input = &self.input[counter..];
+    /// Whether the previous item, like a field or a table header, ends its line:
+    /// it's followed by a newline, a comment, or the end of the input.
+    pub fn at_delimiter(&mut self) -> bool {
+        matches!(
+            self.peek_token(),
+            Token::Newline | Token::Comment(_) | Token::Eof
+        )
+    }
 
-            Some(output)
-        } else {
-            None
-        }
+    pub fn parse<P: Parse<'i>>(&mut self) -> Result<P, P::Err> {
+        P::parse(self)
     }
 }
 
@@ -65,6 +97,45 @@ pub trait Parse<'p>: Sized + Sealed {
     fn parse_str<'str: 'p>(s: &'str str) -> Result<Self, Self::Err> {
         Parser::new(s).parse()
     }
+
+    /// Like [`parse_str`](Self::parse_str), but reports where in `s` parsing failed.
+    fn parse_located<'str: 'p>(s: &'str str) -> Result<Self, Located<Self::Err>> {
+        let mut input = Parser::new(s);
+
+        input.parse().map_err(|error| {
+            let (line, column) = line_column(s, input.offset());
+
+            Located {
+                error,
+                line,
+                column,
+            }
+        })
+    }
+}
+
+/// The line and column, both starting at 1, of the byte `offset` in `s`.
+pub(crate) fn line_column(s: &str, offset: usize) -> (usize, usize) {
+    let line_start = s[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+
+    (
+        s[..offset].matches('\n').count() + 1,
+        s[line_start..offset].chars().count() + 1,
+    )
+}
+
+/// An error along with the line and column, both starting at 1, where it was found.
+#[derive(Debug)]
+pub struct Located<E> {
+    pub error: E,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<E: fmt::Display> fmt::Display for Located<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.error)
+    }
 }
 
 impl Sealed for u64 {}
@@ -72,12 +143,13 @@ impl<'p> Parse<'p> for u64 {
     type Err = std::num::ParseIntError;
 
     fn parse(input: &mut Parser<'p>) -> Result<Self, Self::Err> {
-        let mut digits = String::new();
-
-        while let Some(digit) = input.parse_char_with(|ch| ch.is_ascii_digit()) {
-            digits.push(digit)
+        match input.peek_token() {
+            Token::Bare(digits) if digits.starts_with(|ch: char| ch.is_ascii_digit()) => {
+                let int = digits.parse()?;
+                input.next_lexeme();
+                Ok(int)
+            }
+            _ => "".parse(),
         }
-
-        digits.parse()
     }
 }
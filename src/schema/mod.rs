@@ -0,0 +1,603 @@
+/*!
+Declaring what a valid document looks like, with the `schema` feature.
+
+A [`Schema`] holds a [`Rule`] for each key it constrains. Schemas are written as nvee documents,
+or as TOML files with the `toml` feature, in which each table names a key and its fields are the rule:
+
+```text
+[db.port]
+type = "integer"
+min = 1
+max = 65535
+default = 5432
+
+[db.url]
+required = "true"
+pattern = "^postgres://"
+
+[log.level]
+enum = ["debug", "info", "warn"]
+default = "info"
+```
+
+A rule may have:
+- `type`: one of `string`, `integer`, `array`, `datetime`, `duration`, `size` and `path`.
+- `required`: `"true"` if the key must be set, unless it has a default. In TOML, this is a boolean.
+- `min` and `max`: inclusive bounds, which only integers can meet.
+- `pattern`: a regular expression that has to match somewhere in the value's text; anchor it with `^` and `$` to match all of it.
+- `enum`: an array of the allowed values.
+- `default`: the value used when the key isn't set.
+
+[`Document::validate`] reports every value that breaks its rule, and [`Document::fill_defaults`] sets defaults.
+With [`LoadOptions::schema`](crate::LoadOptions::schema), both happen as documents are loaded.
+*/
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use regex::Regex;
+
+use crate::{
+    model::{document, key::KeyRef},
+    Document, Ident, Key, Located, Parse, Table, Value,
+};
+
+pub use crate::model::document::Violation;
+
+/**
+The rules a document has to follow.
+
+```
+use nvee::{schema::{Problem, Schema}, Document, Parse};
+
+let schema = Schema::parse("[port]\ntype = \"integer\"\nmax = 65535\n\n[host]\ndefault = \"localhost\"\n").unwrap();
+let mut document = Document::parse_str("port = 80000\n").unwrap();
+
+let violations = document.validate(&schema).unwrap_err();
+assert_eq!(violations[0].problem(), &Problem::Above(65535));
+assert_eq!(violations[0].locate("port = 80000\n"), Some((1, 1)));
+
+document.fill_defaults(&schema);
+assert!(document.vars().any(|var| var == ("HOST".to_owned(), "localhost".to_owned())));
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    rules: Vec<Rule>,
+}
+
+/// What one key's value has to be, if it's set at all.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    key: Vec<String>,
+    required: bool,
+    value_type: Option<Type>,
+    min: Option<u64>,
+    max: Option<u64>,
+    pattern: Option<Regex>,
+    /// The allowed values, as nvee text, so they can be compared with any value.
+    allowed: Option<Vec<String>>,
+    /// The default value as nvee text, so the schema doesn't borrow the text it was read from.
+    default: Option<String>,
+}
+
+/// The types a [`Rule`] can require, each covering one or more kinds of [`Value`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    /// Strings, and `@file` values, which load as strings.
+    String,
+    Integer,
+    Array,
+    /// Dates, times, and both combined, with or without an offset.
+    DateTime,
+    Duration,
+    Size,
+    Path,
+}
+
+/// What's wrong with a value, or its absence, according to its rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The key is required, but isn't set.
+    Missing,
+    Type {
+        expected: Type,
+        found: Type,
+    },
+    /// An integer below the rule's `min`.
+    Below(u64),
+    /// An integer above the rule's `max`.
+    Above(u64),
+    /// Text that doesn't match the rule's `pattern`.
+    Pattern(String),
+    /// A value that isn't one of the rule's `enum`, which are written as in a document.
+    NotAllowed(Vec<String>),
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(Located<document::ParseError>),
+    #[cfg(feature = "toml")]
+    Import(crate::import::Error),
+    Io(std::io::Error),
+    /// A key that isn't an attribute of a rule, like `port.kind` or a top-level `port`.
+    UnknownAttribute(String),
+    /// An attribute with a value it can't have, like `min = "one"`, along with what it should have been.
+    InvalidAttribute {
+        key: String,
+        expected: &'static str,
+    },
+    Pattern {
+        key: String,
+        source: regex::Error,
+    },
+    /// A default that breaks its own rule.
+    Default {
+        key: String,
+        problem: Problem,
+    },
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Array => "array",
+            Self::DateTime => "datetime",
+            Self::Duration => "duration",
+            Self::Size => "size",
+            Self::Path => "path",
+        })
+    }
+}
+
+impl FromStr for Type {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(Self::String),
+            "integer" => Ok(Self::Integer),
+            "array" => Ok(Self::Array),
+            "datetime" => Ok(Self::DateTime),
+            "duration" => Ok(Self::Duration),
+            "size" => Ok(Self::Size),
+            "path" => Ok(Self::Path),
+            _ => Err(format!("unknown type `{s}`")),
+        }
+    }
+}
+
+impl Type {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) | Value::File(_) => Self::String,
+            Value::Integer(_) => Self::Integer,
+            Value::Array(_) => Self::Array,
+            Value::OffsetDateTime(..)
+            | Value::LocalDateTime(..)
+            | Value::LocalDate(_)
+            | Value::LocalTime(_) => Self::DateTime,
+            Value::Duration(_) => Self::Duration,
+            Value::Size(_) => Self::Size,
+            Value::Path(_) => Self::Path,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "is required"),
+            Self::Type { expected, found } => write!(f, "must be of type {expected}, not {found}"),
+            Self::Below(min) => write!(f, "must be at least {min}"),
+            Self::Above(max) => write!(f, "must be at most {max}"),
+            Self::Pattern(pattern) => write!(f, "must match `{pattern}`"),
+            Self::NotAllowed(allowed) => write!(f, "must be one of {}", allowed.join(", ")),
+        }
+    }
+}
+
+/// A [`Violation`] that owns its key, along with the file, line and column it was found at, if any, as kept by [`Error::Schema`](crate::Error::Schema).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocatedViolation {
+    key: String,
+    problem: Problem,
+    location: Option<(PathBuf, usize, usize)>,
+}
+
+impl LocatedViolation {
+    pub(crate) fn new(violation: &Violation, location: Option<(&Path, usize, usize)>) -> Self {
+        Self {
+            key: violation.key().to_owned(),
+            problem: violation.problem().clone(),
+            location: location.map(|(path, line, column)| (path.to_owned(), line, column)),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn problem(&self) -> &Problem {
+        &self.problem
+    }
+
+    /// The file, line and column of the value's key; see [`Violation::locate`] for which have none.
+    pub fn location(&self) -> Option<(&Path, usize, usize)> {
+        let (path, line, column) = self.location.as_ref()?;
+        Some((path, *line, *column))
+    }
+}
+
+impl fmt::Display for LocatedViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((path, line, column)) = self.location() {
+            write!(f, "{}:{line}:{column}: ", path.display())?;
+        }
+
+        write!(f, "`{}` {}", self.key, self.problem)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(f),
+            #[cfg(feature = "toml")]
+            Self::Import(err) => err.fmt(f),
+            Self::Io(err) => err.fmt(f),
+            Self::UnknownAttribute(key) => write!(f, "`{key}` isn't a rule attribute"),
+            Self::InvalidAttribute { key, expected } => write!(f, "`{key}` must be {expected}"),
+            Self::Pattern { key, source } => write!(f, "`{key}`: {source}"),
+            Self::Default { key, problem } => write!(f, "the default of `{key}` {problem}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Pattern { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the schema at `path`, as TOML if its extension is `.toml` and the `toml` feature is enabled.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(&path)?;
+
+        #[cfg(feature = "toml")]
+        if path.as_ref().extension().is_some_and(|ext| ext == "toml") {
+            return Self::from_toml(&text);
+        }
+
+        Self::parse(&text)
+    }
+
+    /// Reads a schema written as an nvee document.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let document = Document::parse_located(text).map_err(Error::Parse)?;
+
+        Self::from_document(&document)
+    }
+
+    /// Reads a schema written as TOML, with arrays for `enum`s and booleans for `required`.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let imported = crate::import::toml::parse(text, crate::import::ArrayPolicy::Join)
+            .map_err(Error::Import)?;
+
+        Self::from_document(&imported.document().map_err(Error::Import)?)
+    }
+
+    /// Reads the rules in a document, in which every key is a rule's key followed by one of its attributes.
+    pub fn from_document(document: &Document) -> Result<Self, Error> {
+        let mut schema = Self::new();
+
+        let fields = document
+            .fields()
+            .map(|field| (KeyRef::new(&[], field.key()), field.value()));
+        let table_fields = document
+            .tables()
+            .flat_map(Table::fields)
+            .map(|field| (field.key(), field.value()));
+
+        for (key, value) in fields.chain(table_fields) {
+            let segments: Vec<Ident> = key.segments().collect();
+
+            let Some((attribute, rule_key)) =
+                segments.split_last().filter(|(_, rule)| !rule.is_empty())
+            else {
+                return Err(Error::UnknownAttribute(key.to_string()));
+            };
+
+            let index = match schema.rules.iter().position(|rule| {
+                rule.key
+                    .iter()
+                    .map(String::as_str)
+                    .eq(rule_key.iter().map(|segment| segment.as_str()))
+            }) {
+                Some(index) => index,
+                None => {
+                    schema.rules.push(Rule::new(rule_key));
+                    schema.rules.len() - 1
+                }
+            };
+
+            schema.rules[index].set(attribute, value, || key.to_string())?;
+        }
+
+        for rule in &schema.rules {
+            if let Some(problem) = rule
+                .default()
+                .and_then(|default| rule.problems(Some(&default)).into_iter().next())
+            {
+                return Err(Error::Default {
+                    key: rule.key().to_string(),
+                    problem,
+                });
+            }
+        }
+
+        Ok(schema)
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.iter()
+    }
+}
+
+impl Rule {
+    fn new(key: &[Ident]) -> Self {
+        Self {
+            key: key
+                .iter()
+                .map(|segment| segment.as_str().to_owned())
+                .collect(),
+            required: false,
+            value_type: None,
+            min: None,
+            max: None,
+            pattern: None,
+            allowed: None,
+            default: None,
+        }
+    }
+
+    /// Sets an attribute from its value in a schema document, whose full key `key` returns for errors.
+    fn set(
+        &mut self,
+        attribute: &str,
+        value: &Value,
+        key: impl Fn() -> String,
+    ) -> Result<(), Error> {
+        let invalid = |expected| Error::InvalidAttribute {
+            key: key(),
+            expected,
+        };
+
+        match (attribute, value) {
+            ("type", Value::String(name)) => {
+                self.value_type = Some(name.parse().map_err(|_| {
+                    invalid("one of string, integer, array, datetime, duration, size and path")
+                })?)
+            }
+            ("required", Value::String("true")) => self.required = true,
+            ("required", Value::String("false")) => self.required = false,
+            ("required", _) => return Err(invalid("\"true\" or \"false\"")),
+            ("min", Value::Integer(min)) => self.min = Some(*min),
+            ("max", Value::Integer(max)) => self.max = Some(*max),
+            ("min" | "max", _) => return Err(invalid("an integer")),
+            ("pattern", Value::String(pattern)) => {
+                self.pattern = Some(
+                    Regex::new(pattern).map_err(|source| Error::Pattern { key: key(), source })?,
+                )
+            }
+            ("type" | "pattern", _) => return Err(invalid("a string")),
+            ("enum", Value::Array(items)) => {
+                self.allowed = Some(items.iter().map(Value::to_string).collect())
+            }
+            ("enum", _) => return Err(invalid("an array")),
+            ("default", value) => {
+                let text = value.to_string();
+
+                // Strings aren't escaped when written, so not every string can be read back.
+                if Value::parse_str(&text).ok().as_ref() != Some(value) {
+                    return Err(invalid("a value that can be written in a document"));
+                }
+
+                self.default = Some(text);
+            }
+            _ => return Err(Error::UnknownAttribute(key())),
+        }
+
+        Ok(())
+    }
+
+    pub fn key(&self) -> Key<'_> {
+        Key::from_segments(self.key.iter().map(String::as_str))
+            .expect("rule keys are made from the segments of a key")
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub fn value_type(&self) -> Option<Type> {
+        self.value_type
+    }
+
+    pub fn default(&self) -> Option<Value<'_>> {
+        self.default
+            .as_deref()
+            .and_then(|text| Value::parse_str(text).ok())
+    }
+
+    /// Checks a value, or `None` if the key isn't set, reporting every problem with it.
+    pub(crate) fn problems(&self, value: Option<&Value>) -> Vec<Problem> {
+        let Some(value) = value else {
+            return if self.required && self.default.is_none() {
+                vec![Problem::Missing]
+            } else {
+                Vec::new()
+            };
+        };
+
+        let found = Type::of(value);
+
+        if let Some(expected) = self.value_type.filter(|&expected| expected != found) {
+            return vec![Problem::Type { expected, found }];
+        }
+
+        let mut problems = Vec::new();
+
+        if self.min.is_some() || self.max.is_some() {
+            let Value::Integer(int) = *value else {
+                return vec![Problem::Type {
+                    expected: Type::Integer,
+                    found,
+                }];
+            };
+
+            if let Some(min) = self.min.filter(|&min| int < min) {
+                problems.push(Problem::Below(min));
+            }
+
+            if let Some(max) = self.max.filter(|&max| int > max) {
+                problems.push(Problem::Above(max));
+            }
+        }
+
+        if let Some(pattern) = self
+            .pattern
+            .as_ref()
+            .filter(|pattern| !pattern.is_match(&value.var()))
+        {
+            problems.push(Problem::Pattern(pattern.as_str().to_owned()));
+        }
+
+        if let Some(allowed) = self
+            .allowed
+            .as_ref()
+            .filter(|allowed| !allowed.contains(&value.to_string()))
+        {
+            problems.push(Problem::NotAllowed(allowed.clone()));
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Problem, Schema, Type};
+    use crate::Value;
+
+    #[test]
+    fn parse() {
+        let schema = Schema::parse(
+            "[db.port]\ntype = \"integer\"\nrequired = \"true\"\nmin = 1\ndefault = 5432\n\n[level]\nenum = [\"debug\", \"info\"]\n",
+        )
+        .expect("schema should be valid");
+
+        let rules: Vec<_> = schema.rules().collect();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].key().to_string(), "db.port");
+        assert!(rules[0].is_required());
+        assert_eq!(rules[0].value_type(), Some(Type::Integer));
+        assert_eq!(rules[0].default(), Some(Value::Integer(5432)));
+        assert!(!rules[1].is_required());
+        assert_eq!(
+            rules[1].problems(Some(&Value::String("trace"))),
+            [Problem::NotAllowed(vec![
+                "\"debug\"".to_owned(),
+                "\"info\"".to_owned()
+            ])]
+        );
+    }
+
+    #[test]
+    fn problems() {
+        let schema = Schema::parse(
+            "[port]\nmin = 1024\nmax = 65535\n\n[url]\npattern = \"^https://\"\nenum = [\"http://a\", \"https://b\"]\nrequired = \"true\"\n",
+        )
+        .expect("schema should be valid");
+        let [port, url] = [0, 1].map(|index| schema.rules().nth(index).expect("rule should exist"));
+
+        assert_eq!(
+            port.problems(Some(&Value::Integer(80))),
+            [Problem::Below(1024)]
+        );
+        assert_eq!(port.problems(Some(&Value::Integer(2020))), []);
+        assert_eq!(port.problems(None), []);
+        assert_eq!(
+            port.problems(Some(&Value::String("80"))),
+            [Problem::Type {
+                expected: Type::Integer,
+                found: Type::String
+            }]
+        );
+
+        assert_eq!(url.problems(None), [Problem::Missing]);
+        assert_eq!(url.problems(Some(&Value::String("https://b"))), []);
+        assert_eq!(
+            url.problems(Some(&Value::String("http://b"))).len(),
+            2,
+            "both the pattern and enum should be reported"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        for (text, expected) in [
+            ("port = 1", "`port` isn't a rule attribute"),
+            ("[port]\nkind = \"integer\"", "`port.kind` isn't a rule attribute"),
+            ("[port]\nmin = \"one\"", "`port.min` must be an integer"),
+            ("[port]\nrequired = 1", "`port.required` must be \"true\" or \"false\""),
+            ("[port]\ntype = \"number\"", "`port.type` must be one of string, integer, array, datetime, duration, size and path"),
+            ("[port]\nmax = 10\ndefault = 20", "the default of `port` must be at most 10"),
+        ] {
+            let err = Schema::parse(text).expect_err("schema should be invalid");
+
+            assert_eq!(err.to_string(), expected, "{text:?}");
+        }
+
+        assert!(matches!(
+            Schema::parse("[url]\npattern = \"(\""),
+            Err(Error::Pattern { .. })
+        ));
+        assert!(matches!(Schema::parse("[port"), Err(Error::Parse(_))));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml() {
+        let schema = Schema::from_toml(
+            "[db.port]\ntype = \"integer\"\nrequired = true\n\n[log.level]\nenum = [\"debug\", \"info\"]\ndefault = \"info\"\n",
+        )
+        .expect("schema should be valid");
+
+        let keys: Vec<_> = schema.rules().map(|rule| rule.key().to_string()).collect();
+        assert_eq!(keys, ["db.port", "log.level"]);
+        assert!(schema.rules().next().is_some_and(|rule| rule.is_required()));
+    }
+}
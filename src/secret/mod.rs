@@ -0,0 +1,100 @@
+/*!
+Telling which variables hold secrets, so their values can be hidden or stored apart.
+*/
+
+/// Words that mark a variable as holding a secret wherever they appear, like `PASSWORD` in `DB_PASSWORD`.
+const SECRET_WORDS: [&str; 6] = [
+    "PASSWORD",
+    "PASSWD",
+    "PASSPHRASE",
+    "SECRET",
+    "TOKEN",
+    "CREDENTIALS",
+];
+
+/// Words that make a `KEY` right after them secret, like `API` in `API_KEY`, unlike `PUBLIC` in `SSH_PUBLIC_KEY`.
+const SECRET_KEY_KINDS: [&str; 8] = [
+    "API",
+    "PRIVATE",
+    "SECRET",
+    "ACCESS",
+    "AUTH",
+    "SIGNING",
+    "ENCRYPTION",
+    "MASTER",
+];
+
+/**
+Whether a variable's name suggests it holds a secret, so its value shouldn't be shown.
+
+The name is split into words at anything that isn't a letter or digit and at lowercase-to-uppercase
+changes. It's secret if one of the words marks a secret, like in `DB_PASSWORD` or `authToken`,
+or if it has a `KEY` of a secret kind, like `api-key` or `TLS_PRIVATE_KEY`.
+`KEY`, `PRIVATE` and `AUTH` alone aren't enough, so `KEYBOARD_LAYOUT`, `SSH_PUBLIC_KEY` and `PRIVATE_NETWORK` aren't secret.
+*/
+pub fn is_secret(name: &str) -> bool {
+    let words = words(name);
+    let is = |word: &str, list: &[&str]| list.iter().any(|w| w.eq_ignore_ascii_case(word));
+
+    words.iter().any(|word| is(word, &SECRET_WORDS))
+        || words
+            .windows(2)
+            .any(|pair| is(pair[0], &SECRET_KEY_KINDS) && pair[1].eq_ignore_ascii_case("KEY"))
+}
+
+/// Splits `name` into words, like `authToken` into `auth` and `Token`.
+fn words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+
+    for (index, ch) in name.char_indices() {
+        let camel = previous.is_some_and(char::is_lowercase) && ch.is_uppercase();
+
+        if !ch.is_alphanumeric() || camel {
+            words.push(&name[start..index]);
+            start = if camel { index } else { index + ch.len_utf8() };
+        }
+
+        previous = Some(ch);
+    }
+
+    words.push(&name[start..]);
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_secret;
+
+    #[test]
+    fn secrets() {
+        for name in [
+            "DB_PASSWORD",
+            "api-key",
+            "apiKey",
+            "authToken",
+            "SECRET",
+            "GITHUB_TOKEN",
+            "TLS_PRIVATE_KEY",
+            "AWS_SECRET_ACCESS_KEY",
+            "AUTH_KEY",
+        ] {
+            assert!(is_secret(name), "{name}");
+        }
+
+        for name in [
+            "KEYBOARD_LAYOUT",
+            "DB_PORT",
+            "TOKENIZER",
+            "monkey",
+            "",
+            "SSH_PUBLIC_KEY",
+            "PRIVATE_NETWORK",
+            "AUTH_URL",
+            "CACHE_KEY",
+        ] {
+            assert!(!is_secret(name), "{name}");
+        }
+    }
+}
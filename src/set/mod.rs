@@ -1,52 +1,187 @@
-use std::{collections::HashSet, hash::Hash};
+use std::{fmt, hash::BuildHasher};
 
-use crate::Key;
+use hashbrown::HashTable;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Set<'item, Item: KeyEq> {
-    inner: HashSet<HashByKey<'item, Item>>,
+use crate::{Ident, Key};
+
+/**
+A collection of items with unique [`Key`]s, kept in insertion order.
+
+Items are looked up by borrowed key segments, so a [`Key`] or any slice of [`Ident`]s works as a lookup key.
+*/
+#[derive(Clone)]
+pub struct Set<Item: KeyEq> {
+    inner: Vec<Item>,
+    /// The position of every item in `inner`, hashed by its key.
+    index: HashTable<usize>,
+    hasher: std::hash::RandomState,
 }
 
-impl<T: KeyEq> Set<'_, T> {
+impl<T: KeyEq> Set<T> {
     pub fn new() -> Self {
         Self::default()
     }
 }
 
-impl<T: KeyEq> Default for Set<'_, T> {
+impl<T: KeyEq> Default for Set<T> {
     fn default() -> Self {
         Self {
             inner: Default::default(),
+            index: HashTable::new(),
+            hasher: Default::default(),
         }
     }
 }
 
-impl<'a, Item> Set<'a, Item>
+impl<Item: KeyEq + fmt::Debug> fmt::Debug for Set<Item> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.inner).finish()
+    }
+}
+
+impl<Item> Set<Item>
 where
     Item: KeyEq,
 {
-    pub fn get(&'a self, key: Key<'a>) -> Option<&'a Item> {
-        self.inner
-            .get(&HashByKey::Dummy(key))
-            .map(|wrapper| wrapper.item().expect("items in set should be wrappers"))
+    fn hash(&self, key: &[Ident]) -> u64 {
+        self.hasher.hash_one(key)
+    }
+
+    fn position(&self, key: &[Ident]) -> Option<usize> {
+        self.index
+            .find(self.hash(key), |&index| **self.inner[index].key() == *key)
+            .copied()
+    }
+
+    pub fn get(&self, key: &[Ident]) -> Option<&Item> {
+        self.position(key).map(|index| &self.inner[index])
+    }
+
+    /**
+    Returns a mutable reference to the item with the given key.
+
+    The item's key mustn't be changed through this reference, or it can no longer be found by either key.
+    */
+    pub fn get_mut(&mut self, key: &[Ident]) -> Option<&mut Item> {
+        self.position(key).map(|index| &mut self.inner[index])
     }
 
-    pub fn contains(&self, key: Key) -> bool {
-        self.get(key).is_some()
+    pub fn contains(&self, key: &[Ident]) -> bool {
+        self.position(key).is_some()
     }
 
+    /// Adds an item to the set, unless an item with the same key is already present.
+    ///
+    /// Returns whether the item was inserted.
     pub fn insert(&mut self, item: Item) -> bool {
-        self.inner.insert(HashByKey::Item(item))
+        self.try_insert(item).is_ok()
+    }
+
+    /// Like [`insert`](Self::insert), but hands the item back if an item with the same key is already present.
+    pub fn try_insert(&mut self, item: Item) -> Result<(), Item> {
+        if self.contains(item.key()) {
+            Err(item)
+        } else {
+            self.push(item);
+            Ok(())
+        }
+    }
+
+    /// Adds an item whose key isn't in the set yet.
+    fn push(&mut self, item: Item) {
+        let Self {
+            inner,
+            index,
+            hasher,
+        } = self;
+
+        index.insert_unique(hasher.hash_one(&**item.key()), inner.len(), |&index| {
+            hasher.hash_one(&**inner[index].key())
+        });
+        inner.push(item);
+    }
+
+    /// Adds an item to the set, replacing any item with the same key in place.
+    ///
+    /// Returns the replaced item, if there was one.
+    pub fn replace(&mut self, item: Item) -> Option<Item> {
+        if let Some(index) = self.position(item.key()) {
+            Some(std::mem::replace(&mut self.inner[index], item))
+        } else {
+            self.push(item);
+            None
+        }
+    }
+
+    pub fn remove(&mut self, key: &[Ident]) -> Option<Item> {
+        let hash = self.hash(key);
+        let inner = &self.inner;
+        let (removed, _) = self
+            .index
+            .find_entry(hash, |&index| **inner[index].key() == *key)
+            .ok()?
+            .remove();
+
+        // Every later item moves back one place.
+        for index in self.index.iter_mut() {
+            if *index > removed {
+                *index -= 1;
+            }
+        }
+
+        Some(self.inner.remove(removed))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
     }
 
-    pub fn iter(&self) -> Iter<Item> {
+    pub fn iter(&self) -> Iter<'_, Item> {
         Iter {
             inner: self.inner.iter(),
         }
     }
+
+    /// Iterates over mutable references to the items, whose keys mustn't be changed, as with [`get_mut`](Self::get_mut).
+    pub fn iter_mut(&mut self) -> IterMut<'_, Item> {
+        IterMut {
+            inner: self.inner.iter_mut(),
+        }
+    }
 }
 
-impl<'set, Item> IntoIterator for &'set Set<'_, Item>
+/// Sets are equal if they contain equal items, regardless of order.
+impl<Item> PartialEq for Set<Item>
+where
+    Item: KeyEq + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|item| other.get(item.key()).is_some_and(|other| item == other))
+    }
+}
+
+impl<Item> Eq for Set<Item> where Item: KeyEq + Eq {}
+
+impl<Item: KeyEq> FromIterator<Item> for Set<Item> {
+    fn from_iter<T: IntoIterator<Item = Item>>(iter: T) -> Self {
+        let mut set = Self::new();
+
+        for item in iter {
+            set.replace(item);
+        }
+
+        set
+    }
+}
+
+impl<'set, Item> IntoIterator for &'set Set<Item>
 where
     Item: KeyEq,
 {
@@ -59,31 +194,43 @@ where
 }
 
 pub struct Iter<'set, Item> {
-    inner: std::collections::hash_set::Iter<'set, HashByKey<'set, Item>>,
+    inner: std::slice::Iter<'set, Item>,
 }
 
 impl<'set, Item> Iterator for Iter<'set, Item> {
     type Item = &'set Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().and_then(HashByKey::item)
+        self.inner.next()
+    }
+}
+
+pub struct IterMut<'set, Item> {
+    inner: std::slice::IterMut<'set, Item>,
+}
+
+impl<'set, Item> Iterator for IterMut<'set, Item> {
+    type Item = &'set mut Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
     }
 }
 
-pub struct IntoIter<'item, Item> {
-    inner: std::collections::hash_set::IntoIter<HashByKey<'item, Item>>,
+pub struct IntoIter<Item> {
+    inner: std::vec::IntoIter<Item>,
 }
 
-impl<Item> Iterator for IntoIter<'_, Item> {
+impl<Item> Iterator for IntoIter<Item> {
     type Item = Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next().and_then(HashByKey::into_item)
+        self.inner.next()
     }
 }
 
-impl<'item, Item: KeyEq> IntoIterator for Set<'item, Item> {
-    type IntoIter = IntoIter<'item, Item>;
+impl<Item: KeyEq> IntoIterator for Set<Item> {
+    type IntoIter = IntoIter<Item>;
     type Item = Item;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -94,69 +241,73 @@ impl<'item, Item: KeyEq> IntoIterator for Set<'item, Item> {
 }
 
 pub trait KeyEq: crate::Sealed {
-    fn key(&self) -> &Key;
+    fn key(&self) -> &Key<'_>;
 
     fn key_eq(&self, other: &impl KeyEq) -> bool {
         self.key() == other.key()
     }
 }
 
-#[derive(Clone, Debug)]
-enum HashByKey<'k, T> {
-    Item(T),
-    Dummy(Key<'k>),
-}
+#[cfg(test)]
+mod tests {
+    use super::Set;
+    use crate::{Field, Value};
 
-impl<T> HashByKey<'_, T> {
-    fn item(&self) -> Option<&T> {
-        if let Self::Item(t) = self {
-            Some(t)
-        } else {
-            None
-        }
+    fn field(key: &'static [&'static str], value: u64) -> Field<'static> {
+        crate::model::field::CONSTRUCTOR((key, Value::Integer(value)))
     }
 
-    fn into_item(self) -> Option<T> {
-        if let Self::Item(t) = self {
-            Some(t)
-        } else {
-            None
-        }
-    }
-}
+    #[test]
+    fn insert_keeps_first() {
+        let mut set = Set::new();
 
-impl<T> From<T> for HashByKey<'_, T> {
-    fn from(value: T) -> Self {
-        Self::Item(value)
+        assert!(set.insert(field(&["a"], 1)));
+        assert!(!set.insert(field(&["a"], 2)));
+
+        assert_eq!(set.get(field(&["a"], 0).key()), Some(&field(&["a"], 1)));
     }
-}
 
-impl<T: KeyEq> crate::Sealed for HashByKey<'_, T> {}
-impl<T: KeyEq> KeyEq for HashByKey<'_, T> {
-    fn key(&self) -> &crate::Key {
-        match self {
-            Self::Dummy(key) => key,
-            Self::Item(item) => item.key(),
-        }
+    #[test]
+    fn replace_and_remove() {
+        let mut set = Set::new();
+        set.insert(field(&["a"], 1));
+        set.insert(field(&["b"], 2));
+
+        assert_eq!(set.replace(field(&["a"], 3)), Some(field(&["a"], 1)));
+        assert_eq!(
+            set.iter().cloned().collect::<Vec<_>>(),
+            [field(&["a"], 3), field(&["b"], 2)]
+        );
+
+        assert_eq!(set.remove(field(&["b"], 0).key()), Some(field(&["b"], 2)));
+        assert_eq!(set.remove(field(&["b"], 0).key()), None);
+        assert_eq!(set.len(), 1);
     }
-}
 
-impl<T> PartialEq for HashByKey<'_, T>
-where
-    Self: KeyEq,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.key() == other.key()
+    #[test]
+    fn remove_keeps_lookups() {
+        let mut set: Set<_> = (0..100)
+            .map(|n| field(&["a", "b"][..n % 2 + 1], n as u64))
+            .collect();
+        set.insert(field(&["c"], 7));
+
+        assert_eq!(set.remove(field(&["a"], 0).key()), Some(field(&["a"], 98)));
+        assert_eq!(
+            set.get(field(&["a", "b"], 0).key()),
+            Some(&field(&["a", "b"], 99))
+        );
+        assert_eq!(set.get(field(&["c"], 0).key()), Some(&field(&["c"], 7)));
+        assert_eq!(
+            set.iter().cloned().collect::<Vec<_>>(),
+            [field(&["a", "b"], 99), field(&["c"], 7)]
+        );
     }
-}
 
-impl<T> Eq for HashByKey<'_, T> where Self: KeyEq {}
+    #[test]
+    fn eq_ignores_order() {
+        let ab: Set<_> = [field(&["a"], 1), field(&["b"], 2)].into_iter().collect();
+        let ba: Set<_> = [field(&["b"], 2), field(&["a"], 1)].into_iter().collect();
 
-impl<T> Hash for HashByKey<'_, T>
-where
-    Self: KeyEq,
-{
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.key().hash(state)
+        assert_eq!(ab, ba);
     }
 }
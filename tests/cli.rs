@@ -0,0 +1,167 @@
+use std::process::{Command, Output};
+
+fn nvee(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nvee"))
+        .args(args)
+        .current_dir("tests/files")
+        .output()
+        .expect("nvee should run")
+}
+
+#[test]
+fn run() {
+    let output = nvee(&[
+        "run",
+        "-f",
+        "app.nvee",
+        "--",
+        "sh",
+        "-c",
+        "echo \"$APP_USER:$APP_PASSWORD\"; exit 3",
+    ]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "app:hunter2\n");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn run_missing() {
+    assert_eq!(
+        nvee(&["run", "-f", "app.nvee", "nowhere"]).status.code(),
+        Some(127)
+    );
+    assert_eq!(
+        nvee(&["run", "-f", "missing.nvee", "true"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(nvee(&["run"]).status.code(), Some(2));
+}
+
+#[test]
+fn check() {
+    let valid = nvee(&["check", "app.nvee"]);
+    assert_eq!(valid.status.code(), Some(0));
+
+    let broken = nvee(&["check", "app.nvee", "broken.nvee"]);
+    assert_eq!(broken.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&broken.stderr),
+        "broken.nvee:2:5: unclosed string\n"
+    );
+
+    let shadows = nvee(&["check", ".nvee.shadows"]);
+    assert_eq!(shadows.status.code(), Some(0));
+    assert_eq!(
+        String::from_utf8_lossy(&shadows.stderr),
+        ".nvee.shadows:1:1: warning: `home` becomes the variable HOME, which overrides the well-known variable HOME\n"
+    );
+}
+
+#[test]
+fn get() {
+    for name in ["password", "APP_PASSWORD"] {
+        let output = nvee(&["get", "-f", "app.nvee", name]);
+
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "hunter2\n",
+            "{name}"
+        );
+    }
+
+    assert_eq!(
+        nvee(&["get", "-f", "app.nvee", "nowhere"]).status.code(),
+        Some(1)
+    );
+}
+
+#[test]
+fn list() {
+    let output = nvee(&["list", "-f", "app.nvee"]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "APP_USER=app\nAPP_PASSWORD=********\n"
+    );
+}
+
+#[test]
+fn warnings() {
+    let output = nvee(&["list", "-f", ".nvee.shadows"]);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "HOME=/srv/app\n");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "warning: `home` becomes the variable HOME, which overrides the well-known variable HOME\n"
+    );
+}
+
+#[test]
+fn export() {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg("eval \"$(\"$NVEE\" export -f quoting.nvee)\" && printf %s \"$QUOTING_MESSAGE\"")
+        .env("NVEE", env!("CARGO_BIN_EXE_nvee"))
+        .current_dir("tests/files")
+        .output()
+        .expect("sh should run");
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "it's $HOME `pwd`");
+    assert_eq!(nvee(&["export", "--shell", "cmd"]).status.code(), Some(2));
+}
+
+#[test]
+fn export_formats() {
+    let output = nvee(&["export", "-f", "app.nvee", "--format", "json"]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "{\n  \"APP_USER\": \"app\",\n  \"APP_PASSWORD\": \"hunter2\"\n}\n"
+    );
+    assert_eq!(nvee(&["export", "--format", "yaml"]).status.code(), Some(2));
+}
+
+#[test]
+fn schema() {
+    let valid = nvee(&["check", "--schema", "app.schema.nvee", "app.nvee"]);
+    assert_eq!(valid.status.code(), Some(0));
+
+    let invalid = nvee(&["check", "--schema", "app.schema.nvee", "invalid.nvee"]);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&invalid.stderr),
+        "invalid.nvee:1:1: error: `user` must match `^[a-z]+$`\n\
+         invalid.nvee: error: `password` is required\n\
+         invalid.nvee:4:1: error: `db.port` must be at most 65535\n"
+    );
+
+    // Checked together, app.nvee sets the password that invalid.nvee lacks.
+    let merged = nvee(&[
+        "check",
+        "--schema",
+        "app.schema.nvee",
+        "app.nvee",
+        "invalid.nvee",
+    ]);
+    assert_eq!(merged.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&merged.stderr),
+        "invalid.nvee:1:1: error: `user` must match `^[a-z]+$`\n\
+         invalid.nvee:4:1: error: `db.port` must be at most 65535\n"
+    );
+
+    let output = nvee(&[
+        "get",
+        "-f",
+        "app.nvee",
+        "--schema",
+        "app.schema.nvee",
+        "db.port",
+    ]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "5432\n");
+
+    let output = nvee(&["list", "-f", "invalid.nvee", "--schema", "app.schema.nvee"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .starts_with("nvee: the document doesn't follow its schema:\n    invalid.nvee:1:1: "));
+}
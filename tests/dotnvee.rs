@@ -14,5 +14,6 @@ fn main() {
         ("TABLE_LUCKY_NUMBER", "777"),
         ("ANOTHER_TABLE_EVEN_LUCKIER", "7777"),
         ("ANOTHER_TABLE_SNAKE_EYES", "66"),
+        ("2KV", "1"),
     ]);
 }
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+fn assert_vars(iter: impl IntoIterator<Item = (&'static str, &'static str)>) {
+    for (var, expected) in iter {
+        pretty_assertions::assert_eq!(std::env::var(var).as_deref(), Ok(expected));
+    }
+}
+
+#[test]
+fn main() {
+    nvee::from_path("tests/includes/app.nvee").expect("loading .nvee file should not fail");
+
+    assert_vars([
+        ("APP_NAME", "app"),
+        ("APP_SERVER_PORT", "8080"),
+        ("APP_DB_PRIMARY_URL", "postgres://localhost"),
+        ("APP_DB_PRIMARY_PORT", "5432"),
+        ("APP_CACHE_REDIS_SIZE", "64"),
+    ]);
+}
+
+#[test]
+fn cycle() {
+    match nvee::load::Sources::read("tests/includes/cycle_a.nvee") {
+        Err(nvee::Error::IncludeCycle(chain)) => {
+            let names: Vec<_> = chain.iter().filter_map(|path| path.file_name()).collect();
+            assert_eq!(names, ["cycle_a.nvee", "cycle_b.nvee", "cycle_a.nvee"]);
+        }
+        other => panic!("expected an include cycle, got {other:?}"),
+    }
+}
+
+#[test]
+fn missing() {
+    let mut chain: Vec<PathBuf> = Vec::new();
+    let mut err = nvee::load::Sources::read("tests/includes/broken.nvee")
+        .expect_err("loading a missing include should fail");
+
+    while let nvee::Error::File { path, source } = err {
+        chain.push(path);
+        err = *source;
+    }
+
+    assert!(matches!(err, nvee::Error::Io(_)));
+    assert_eq!(
+        chain,
+        [
+            Path::new("tests/includes/broken.nvee"),
+            Path::new("tests/includes/nowhere.nvee"),
+        ]
+    );
+}
@@ -0,0 +1,25 @@
+use nvee::{Case, LoadOptions, NamingStrategy};
+
+#[test]
+fn main() {
+    let naming = NamingStrategy::new()
+        .separator("__")
+        .case(Case::Preserve)
+        .basename(false)
+        .prefix("APP_");
+
+    LoadOptions::new()
+        .naming(naming)
+        .load("example.nvee")
+        .expect("loading .nvee file should not fail");
+
+    for (var, expected) in [
+        ("APP_author", "kate"),
+        ("APP_table__lucky_number", "777"),
+        ("APP_another_table__snake_eyes", "66"),
+    ] {
+        pretty_assertions::assert_eq!(std::env::var(var).as_deref(), Ok(expected));
+    }
+
+    assert!(std::env::var("EXAMPLE_AUTHOR").is_err());
+}
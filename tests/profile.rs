@@ -0,0 +1,26 @@
+fn assert_vars(iter: impl IntoIterator<Item = (&'static str, Option<&'static str>)>) {
+    for (var, expected) in iter {
+        pretty_assertions::assert_eq!(std::env::var(var).as_deref().ok(), expected);
+    }
+}
+
+#[test]
+fn main() {
+    assert!(matches!(
+        nvee::LoadOptions::new().profile("prdo").load("tests/profiles.nvee"),
+        Err(nvee::Error::UndefinedProfile(name)) if name == "prdo"
+    ));
+
+    nvee::LoadOptions::new()
+        .profile("dev")
+        .load("tests/profiles.nvee")
+        .expect("loading .nvee file should not fail");
+
+    assert_vars([
+        ("PROFILES_DEBUG", Some("0")),
+        ("PROFILES_DB_URL", Some("http://localhost")),
+        ("PROFILES_DB_PORT", Some("2020")),
+        ("PROFILES_PROFILE_DEV_DB_URL", None),
+        ("PROFILES_PROFILE_PROD_DEBUG", None),
+    ]);
+}